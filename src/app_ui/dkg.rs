@@ -0,0 +1,252 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use blake2b_simd::Params as Blake2b;
+use ironfish_frost::participant::Identity;
+use crate::ironfish::address::encode_address;
+use crate::ironfish::constants::IDENTITY_FINGERPRINT_PERSONALIZATION;
+use crate::ironfish::public_address::PUBLIC_ADDRESS_SIZE;
+use crate::nvm::settings::{Settings, EXPERT_MODE};
+
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+use ledger_device_sdk::ui::{
+    bitmaps::{CROSSMARK, EYE, VALIDATE_14},
+    gadgets::{Field, MultiFieldReview},
+};
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use include_gif::include_gif;
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use ledger_device_sdk::nbgl::{Field, NbglGlyph, NbglReview};
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+const APP_ICON: NbglGlyph = NbglGlyph::from_include(include_gif!("stax_icon.gif", NBGL));
+
+/// Bytes of an identity hash shown to tell participants apart
+const FINGERPRINT_LEN: usize = 8;
+
+/// Short hash of an identity, so participants can compare the identities they use
+/// without going through the 129 bytes of each one
+pub fn identity_fingerprint(identity: &Identity) -> String {
+    let hash = Blake2b::new()
+        .hash_length(FINGERPRINT_LEN)
+        .personal(IDENTITY_FINGERPRINT_PERSONALIZATION)
+        .hash(identity.serialize().as_ref());
+
+    hex::encode(hash.as_bytes())
+}
+
+/// Raw participant identities, only shown in expert mode
+fn participant_values(identities: &[Identity]) -> Vec<(String, String)> {
+    let mut values: Vec<(String, String)> = Vec::new();
+    if Settings.is_enabled(EXPERT_MODE) {
+        for (i, identity) in identities.iter().enumerate() {
+            values.push((format!("Participant {}", i + 1), hex::encode(identity.serialize())));
+        }
+    }
+    values
+}
+
+/// Shows the fields, with `titles` on Nano devices and `nbgl_titles` (title,
+/// subtitle, finish title) on Stax and Flex.
+fn show_review(fields: &[Field], titles: &[&str], nbgl_titles: (&str, &str, &str)) -> bool {
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    {
+        let _ = nbgl_titles;
+        let review = MultiFieldReview::new(
+            fields,
+            titles,
+            Some(&EYE),
+            "Approve",
+            Some(&VALIDATE_14),
+            "Reject",
+            Some(&CROSSMARK),
+        );
+
+        review.show()
+    }
+
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    {
+        let _ = titles;
+        let mut review = NbglReview::new()
+            .titles(nbgl_titles.0, nbgl_titles.1, nbgl_titles.2)
+            .glyph(&APP_ICON);
+
+        review.show(fields)
+    }
+}
+
+/// Titles of an account review, `nano` on Nano devices and `nbgl` (title, subtitle,
+/// finish title) on Stax and Flex
+pub struct AccountReviewTitles {
+    pub nano: [&'static str; 2],
+    pub nbgl: (&'static str, &'static str, &'static str),
+}
+
+/// Account obtained at the end of a reshare, before it replaces the stored one
+pub const RESHARED_ACCOUNT: AccountReviewTitles = AccountReviewTitles {
+    nano: ["Save reshared", "account"],
+    nbgl: ("Save reshared account", "Replaces the account on this device", "Save account"),
+};
+
+/// Account a reshare makes us join, when no account is stored on the device. Its
+/// address can only be trusted once compared with the other participants.
+pub const JOINED_ACCOUNT: AccountReviewTitles = AccountReviewTitles {
+    nano: ["Join", "multisig account"],
+    nbgl: ("Join multisig account", "Check the address with the other participants", "Join account"),
+};
+
+/// Account whose shares are about to be refreshed, the account itself doesn't change
+pub const REFRESH: AccountReviewTitles = AccountReviewTitles {
+    nano: ["Refresh", "account shares"],
+    nbgl: ("Refresh account shares", "The account address stays the same", "Refresh shares"),
+};
+
+/// Account whose refreshed shares replace the current ones, once every participant
+/// confirmed them
+pub const REFRESHED_ACCOUNT: AccountReviewTitles = AccountReviewTitles {
+    nano: ["Save refreshed", "shares"],
+    nbgl: ("Save refreshed shares", "Replaces the current shares on this device", "Save shares"),
+};
+
+/// Account generated by a trusted dealer, before it replaces the stored one
+pub const IMPORTED_ACCOUNT: AccountReviewTitles = AccountReviewTitles {
+    nano: ["Import", "multisig account"],
+    nbgl: ("Import multisig account", "Replaces the account on this device", "Import account"),
+};
+
+/// Displays the address and threshold of an account, with the titles of the step
+/// of the ceremony it is reviewed in.
+///
+/// # Returns
+///
+/// * `true` if the user approves, `false` if the user rejects
+#[inline(never)]
+pub fn ui_review_account(
+    titles: &AccountReviewTitles,
+    address: &[u8; PUBLIC_ADDRESS_SIZE],
+    min_signers: u8,
+    participants: usize,
+) -> bool {
+    let address = encode_address(address);
+    let threshold = format!("{} of {}", min_signers, participants);
+
+    let fields = [
+        Field { name: "Address", value: address.as_str() },
+        Field { name: "Threshold", value: threshold.as_str() },
+    ];
+
+    show_review(&fields, &titles.nano, titles.nbgl)
+}
+
+/// Displays the parameters of a new multisig account before the first round of the
+/// DKG ceremony starts. In expert mode every participant identity is shown too.
+///
+/// # Arguments
+///
+/// * `identities` - Identities of all the participants, including ours
+/// * `min_signers` - Number of signatures required to sign a transaction
+///
+/// # Returns
+///
+/// * `true` if the user approves, `false` if the user rejects
+#[inline(never)]
+pub fn ui_review_dkg_round_1(identities: &[Identity], min_signers: u8) -> bool {
+    let participants = format!("{}", identities.len());
+    let threshold = format!("{} of {}", min_signers, identities.len());
+    let values = participant_values(identities);
+
+    let mut fields = Vec::new();
+    fields.push(Field { name: "Participants", value: participants.as_str() });
+    fields.push(Field { name: "Threshold", value: threshold.as_str() });
+    for (name, value) in values.iter() {
+        fields.push(Field { name: name.as_str(), value: value.as_str() });
+    }
+
+    show_review(
+        &fields,
+        &["Create", "multisig account"],
+        ("Create multisig account", "Distributed key generation", "Start key generation"),
+    )
+}
+
+/// Displays the new participants and threshold before our share of the account
+/// key is dealt to them.
+///
+/// # Returns
+///
+/// * `true` if the user approves, `false` if the user rejects
+#[inline(never)]
+pub fn ui_review_reshare(identities: &[Identity], min_signers: u8) -> bool {
+    let participants = format!("{}", identities.len());
+    let threshold = format!("{} of {}", min_signers, identities.len());
+    let values = participant_values(identities);
+
+    let mut fields = Vec::new();
+    fields.push(Field { name: "New participants", value: participants.as_str() });
+    fields.push(Field { name: "New threshold", value: threshold.as_str() });
+    for (name, value) in values.iter() {
+        fields.push(Field { name: name.as_str(), value: value.as_str() });
+    }
+
+    show_review(
+        &fields,
+        &["Reshare", "multisig account"],
+        ("Reshare multisig account", "Issue shares to a new signer set", "Reshare account"),
+    )
+}
+
+/// Asks to erase our share once dealt in a reshare that leaves us out of the new
+/// signer set.
+///
+/// # Returns
+///
+/// * `true` if the user approves, `false` if the user rejects
+#[inline(never)]
+pub fn ui_confirm_share_deletion(address: &[u8; PUBLIC_ADDRESS_SIZE]) -> bool {
+    let address = encode_address(address);
+
+    let fields = [Field { name: "Address", value: address.as_str() }];
+
+    show_review(
+        &fields,
+        &["Delete share", "of old account"],
+        ("Delete old share", "This device is not in the new signer set", "Delete share"),
+    )
+}
+
+/// Displays the account about to be erased from the device
+///
+/// # Returns
+///
+/// * `true` if the user approves, `false` if the user rejects
+#[inline(never)]
+pub fn ui_confirm_account_deletion(address: &[u8; PUBLIC_ADDRESS_SIZE]) -> bool {
+    let address = encode_address(address);
+
+    let fields = [Field { name: "Address", value: address.as_str() }];
+
+    show_review(
+        &fields,
+        &["Delete", "multisig account"],
+        ("Delete multisig account", "Its keys will be erased from this device", "Delete account"),
+    )
+}
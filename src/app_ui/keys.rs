@@ -0,0 +1,123 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use alloc::string::String;
+use crate::ironfish::address::{encode_address, short_address};
+use crate::ironfish::public_address::PUBLIC_ADDRESS_SIZE;
+use crate::nvm::settings::{Settings, ADDRESS_FORMAT, ADDRESS_FORMAT_SHORT};
+
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+use ledger_device_sdk::ui::{
+    bitmaps::{CROSSMARK, EYE, VALIDATE_14},
+    gadgets::{Field, MultiFieldReview},
+};
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use include_gif::include_gif;
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use ledger_device_sdk::nbgl::{Field, NbglAddressReview, NbglGlyph, NbglReview};
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+const APP_ICON: NbglGlyph = NbglGlyph::from_include(include_gif!("stax_icon.gif", NBGL));
+
+/// Address as wallets show it, shortened when the user prefers it in the settings.
+/// Only where the address is context, the address review always shows it in full.
+pub fn display_address(address: &[u8; PUBLIC_ADDRESS_SIZE]) -> String {
+    if Settings.get_element(ADDRESS_FORMAT) != ADDRESS_FORMAT_SHORT {
+        return encode_address(address);
+    }
+
+    short_address(address)
+}
+
+/// Asks the user before keys are sent to the host.
+///
+/// # Arguments
+///
+/// * `keys` - Name of the keys being exported
+/// * `address` - Public address of the account the keys belong to
+///
+/// # Returns
+///
+/// * `true` if the user approves, `false` if the user rejects
+#[inline(never)]
+pub fn ui_approve_key_export(keys: &str, address: &[u8; PUBLIC_ADDRESS_SIZE]) -> bool {
+    let address = display_address(address);
+    let fields = [
+        Field { name: "Export", value: keys },
+        Field { name: "Address", value: address.as_str() },
+    ];
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    {
+        let review = MultiFieldReview::new(
+            &fields,
+            &["Export", "keys"],
+            Some(&EYE),
+            "Approve",
+            Some(&VALIDATE_14),
+            "Reject",
+            Some(&CROSSMARK),
+        );
+
+        review.show()
+    }
+
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    {
+        let mut review = NbglReview::new()
+            .titles("Export keys", "", "Approve export")
+            .glyph(&APP_ICON);
+
+        review.show(&fields)
+    }
+}
+
+/// Displays the multisig account address so the user can check it before it is
+/// sent to the host.
+///
+/// # Returns
+///
+/// * `true` if the user approves, `false` if the user rejects
+#[inline(never)]
+pub fn ui_review_address(address: &[u8; PUBLIC_ADDRESS_SIZE]) -> bool {
+    let address = encode_address(address);
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    {
+        let fields = [Field { name: "Address", value: address.as_str() }];
+        let review = MultiFieldReview::new(
+            &fields,
+            &["Verify", "address"],
+            Some(&EYE),
+            "Approve",
+            Some(&VALIDATE_14),
+            "Reject",
+            Some(&CROSSMARK),
+        );
+
+        review.show()
+    }
+
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    {
+        NbglAddressReview::new()
+            .glyph(&APP_ICON)
+            .verify_str("Verify Ironfish address")
+            .show(address.as_str())
+    }
+}
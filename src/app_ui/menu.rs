@@ -20,23 +20,56 @@ use ledger_device_sdk::io::{Comm, Event};
 
 #[cfg(not(any(target_os = "stax", target_os = "flex")))]
 use ledger_device_sdk::ui::{
-    bitmaps::{Glyph, BACK, CERTIFICATE, DASHBOARD_X},
+    bitmaps::{Glyph, BACK, CERTIFICATE, COGGLE, CROSSMARK, DASHBOARD_X, EYE},
     gadgets::{EventOrPageIndex, MultiPageMenu, Page},
 };
 
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use core::ffi::{c_char, c_int};
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use ledger_device_sdk::nbgl::NbglGlyph;
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use ledger_secure_sdk_sys::{
+    nbgl_content_t, nbgl_contentInfoList_t, nbgl_contentSwitch_t, nbgl_genericContents_t,
+    nbgl_homeAction_t, nbgl_icon_details_t, nbgl_useCaseHomeAndSettings, FIRST_USER_TOKEN,
+    INIT_HOME_PAGE, OFF_STATE, ON_STATE, SWITCHES_LIST, TUNE_TAP_CASUAL,
+};
+
 use crate::Instruction;
+use crate::app_ui::dkg::identity_fingerprint;
+use crate::ironfish::address::short_address;
+use crate::nvm::dkg_keys::DkgKeys;
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use alloc::ffi::CString;
+use alloc::{format, string::String, vec::Vec};
+use crate::nvm::settings::Settings;
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use crate::nvm::settings::NUM_SETTINGS;
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+use crate::nvm::settings::{
+    ADDRESS_FORMAT, ADDRESS_FORMAT_FULL, ADDRESS_FORMAT_SHORT, BLIND_SIGNING, EXPERT_MODE,
+    KEY_EXPORT_APPROVAL,
+};
 
-// use ledger_device_sdk::nvm::*;
+/// What the main loop has to do when the user leaves the home screen
+pub enum MenuEvent {
+    Command(Instruction),
+    /// Run from the main loop like the APDU, so its review is not nested in the menu
+    DeleteAccount,
+}
 
 #[cfg(not(any(target_os = "stax", target_os = "flex")))]
-fn ui_about_menu(comm: &mut Comm) -> Event<Instruction> {
+fn ui_about_menu(comm: &mut Comm) -> MenuEvent {
     let pages = [
         &Page::from((["Ironfish", "Zondax AG"], true)),
         &Page::from(("Back", &BACK)),
     ];
     loop {
         match MultiPageMenu::new(comm, &pages).show() {
-            EventOrPageIndex::Event(e) => return e,
+            EventOrPageIndex::Event(Event::Command(ins)) => return MenuEvent::Command(ins),
+            EventOrPageIndex::Event(_) => (),
             EventOrPageIndex::Index(1) => return ui_menu_main(comm),
             EventOrPageIndex::Index(_) => (),
         }
@@ -44,22 +77,235 @@ fn ui_about_menu(comm: &mut Comm) -> Event<Instruction> {
 }
 
 #[cfg(not(any(target_os = "stax", target_os = "flex")))]
-pub fn ui_menu_main(comm: &mut Comm) -> Event<Instruction> {
+fn enabled_str(index: usize) -> &'static str {
+    if Settings.is_enabled(index) {
+        "Enabled"
+    } else {
+        "Disabled"
+    }
+}
+
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+fn ui_settings_menu(comm: &mut Comm) -> MenuEvent {
+    loop {
+        let address_format = match Settings.get_element(ADDRESS_FORMAT) {
+            ADDRESS_FORMAT_SHORT => "Short",
+            _ => "Full",
+        };
+        let pages = [
+            &Page::from((["Blind signing", enabled_str(BLIND_SIGNING)], true)),
+            &Page::from((["Expert mode", enabled_str(EXPERT_MODE)], true)),
+            &Page::from((["Address format", address_format], true)),
+            &Page::from((["Approve key export", enabled_str(KEY_EXPORT_APPROVAL)], true)),
+            &Page::from(("Back", &BACK)),
+        ];
+
+        // Selecting a setting changes it, the menu is then built again to show the new value
+        match MultiPageMenu::new(comm, &pages).show() {
+            EventOrPageIndex::Event(Event::Command(ins)) => return MenuEvent::Command(ins),
+            EventOrPageIndex::Event(_) => (),
+            EventOrPageIndex::Index(0) => Settings.toggle(BLIND_SIGNING),
+            EventOrPageIndex::Index(1) => Settings.toggle(EXPERT_MODE),
+            EventOrPageIndex::Index(2) => {
+                let next = match Settings.get_element(ADDRESS_FORMAT) {
+                    ADDRESS_FORMAT_SHORT => ADDRESS_FORMAT_FULL,
+                    _ => ADDRESS_FORMAT_SHORT,
+                };
+                Settings.set_element(ADDRESS_FORMAT, next)
+            }
+            EventOrPageIndex::Index(3) => Settings.toggle(KEY_EXPORT_APPROVAL),
+            EventOrPageIndex::Index(4) => return ui_menu_main(comm),
+            EventOrPageIndex::Index(_) => (),
+        }
+    }
+}
+
+/// Threshold, address and participant fingerprints of the stored account, as name and value
+fn account_lines() -> Vec<(String, String)> {
+    let mut lines: Vec<(String, String)> = Vec::new();
+    if let (true, Ok(identities), Ok(min_signers), Ok(address)) = (
+        DkgKeys.has_account(),
+        DkgKeys.load_identities(),
+        DkgKeys.load_min_signers(),
+        DkgKeys.load_public_address(),
+    ) {
+        lines.push((String::from("Threshold"), format!("{} of {}", min_signers, identities.len())));
+        lines.push((String::from("Address"), short_address(&address)));
+        for (i, identity) in identities.iter().enumerate() {
+            lines.push((format!("Participant {}", i + 1), identity_fingerprint(identity)));
+        }
+    } else {
+        lines.push((String::from("No account"), String::from("stored")));
+    }
+    lines
+}
+
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+fn ui_account_menu(comm: &mut Comm) -> MenuEvent {
+    let lines = account_lines();
+
+    let mut pages: Vec<Page> = lines
+        .iter()
+        .map(|(name, value)| Page::from(([name.as_str(), value.as_str()], true)))
+        .collect();
+    pages.push(Page::from(("Back", &BACK)));
+    let page_refs: Vec<&Page> = pages.iter().collect();
+    let back = page_refs.len() - 1;
+
+    loop {
+        match MultiPageMenu::new(comm, &page_refs).show() {
+            EventOrPageIndex::Event(Event::Command(ins)) => return MenuEvent::Command(ins),
+            EventOrPageIndex::Event(_) => (),
+            EventOrPageIndex::Index(i) if i == back => return ui_menu_main(comm),
+            EventOrPageIndex::Index(_) => (),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+pub fn ui_menu_main(comm: &mut Comm) -> MenuEvent {
     const APP_ICON: Glyph = Glyph::from_include(include_gif!("nanox_icon.gif"));
     let pages = [
         // The from trait allows to create different styles of pages
         // without having to use the new() function.
         &Page::from((["Ironfish", "is ready"], &APP_ICON)),
         &Page::from((["Version", env!("CARGO_PKG_VERSION")], true)),
+        &Page::from(("Settings", &COGGLE)),
+        &Page::from(("Account", &EYE)),
+        &Page::from(("Delete account", &CROSSMARK)),
         &Page::from(("About", &CERTIFICATE)),
         &Page::from(("Quit", &DASHBOARD_X)),
     ];
     loop {
         match MultiPageMenu::new(comm, &pages).show() {
-            EventOrPageIndex::Event(e) => return e,
-            EventOrPageIndex::Index(2) => return ui_about_menu(comm),
-            EventOrPageIndex::Index(3) => ledger_device_sdk::exit_app(0),
+            EventOrPageIndex::Event(Event::Command(ins)) => return MenuEvent::Command(ins),
+            EventOrPageIndex::Event(_) => (),
+            EventOrPageIndex::Index(2) => return ui_settings_menu(comm),
+            EventOrPageIndex::Index(3) => return ui_account_menu(comm),
+            // Nothing to do without an account, or if the user changes their mind
+            EventOrPageIndex::Index(4) => return MenuEvent::DeleteAccount,
+            EventOrPageIndex::Index(5) => return ui_about_menu(comm),
+            EventOrPageIndex::Index(6) => ledger_device_sdk::exit_app(0),
             EventOrPageIndex::Index(_) => (),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Switches of the settings page, one for each setting with the same index. An
+/// enabled short address format is ADDRESS_FORMAT_SHORT.
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+const SETTINGS_STRINGS: [[&str; 2]; NUM_SETTINGS] = [
+    ["Blind signing\0", "Sign requests that only contain a hash.\0"],
+    ["Expert mode\0", "Show randomizers, commitments and identities.\0"],
+    ["Short addresses\0", "Shorten the account address when approving a key export.\0"],
+    ["Approve key export\0", "Ask before the address or keys are sent to the host.\0"],
+];
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+unsafe extern "C" fn settings_callback(token: c_int, _index: u8, _page: c_int) {
+    if let Some(index) = (token as usize).checked_sub(FIRST_USER_TOKEN as usize) {
+        if index < NUM_SETTINGS {
+            Settings.toggle(index);
+        }
+    }
+}
+
+/// Set by the home screen callbacks, which only record what the user chose
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+static QUIT_REQUESTED: AtomicBool = AtomicBool::new(false);
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+static DELETE_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+unsafe extern "C" fn quit_callback() {
+    QUIT_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+unsafe extern "C" fn delete_account_callback() {
+    DELETE_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Home screen of Stax and Flex. The home and settings screen of the SDK has no
+/// room for an action, so it is built here from the NBGL use case, with the same
+/// settings switches and a button to delete the stored account.
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+pub fn ui_menu_main(comm: &mut Comm) -> MenuEvent {
+    const APP_ICON: NbglGlyph = NbglGlyph::from_include(include_gif!("stax_icon.gif", NBGL));
+    let icon: nbgl_icon_details_t = (&APP_ICON).into();
+
+    // Built on every call, the settings or the account may have changed. The
+    // strings must outlive the screen, NBGL only keeps pointers to them.
+    let mut lines: Vec<(CString, CString)> = Vec::new();
+    lines.push((CString::new("Version").unwrap_or_default(), CString::new(env!("CARGO_PKG_VERSION")).unwrap_or_default()));
+    lines.push((CString::new("Developer").unwrap_or_default(), CString::new("Zondax AG").unwrap_or_default()));
+    for (name, value) in account_lines() {
+        lines.push((
+            CString::new(name).unwrap_or_default(),
+            CString::new(value).unwrap_or_default(),
+        ));
+    }
+    let info_types: Vec<*const c_char> = lines.iter().map(|(name, _)| name.as_ptr()).collect();
+    let info_contents: Vec<*const c_char> = lines.iter().map(|(_, value)| value.as_ptr()).collect();
+
+    unsafe {
+        let mut switches: [nbgl_contentSwitch_t; NUM_SETTINGS] = core::mem::zeroed();
+        for (i, switch) in switches.iter_mut().enumerate() {
+            switch.text = SETTINGS_STRINGS[i][0].as_ptr() as *const c_char;
+            switch.subText = SETTINGS_STRINGS[i][1].as_ptr() as *const c_char;
+            switch.initState = (if Settings.is_enabled(i) { ON_STATE } else { OFF_STATE }) as _;
+            switch.token = (FIRST_USER_TOKEN as usize + i) as _;
+            switch.tuneId = TUNE_TAP_CASUAL as _;
+        }
+
+        let mut content: nbgl_content_t = core::mem::zeroed();
+        content.type_ = SWITCHES_LIST;
+        content.content.switchesList.switches = switches.as_ptr();
+        content.content.switchesList.nbSwitches = NUM_SETTINGS as u8;
+        content.contentActionCallback = Some(settings_callback);
+
+        let mut settings: nbgl_genericContents_t = core::mem::zeroed();
+        settings.__bindgen_anon_1.contentsList = &content;
+        settings.nbContents = 1;
+
+        let mut infos: nbgl_contentInfoList_t = core::mem::zeroed();
+        infos.infoTypes = info_types.as_ptr();
+        infos.infoContents = info_contents.as_ptr();
+        infos.nbInfos = info_types.len() as u8;
+
+        let mut delete_action: nbgl_homeAction_t = core::mem::zeroed();
+        delete_action.text = "Delete account\0".as_ptr() as *const c_char;
+        delete_action.callback = Some(delete_account_callback);
+        let action: *const nbgl_homeAction_t = if DkgKeys.has_account() {
+            &delete_action
+        } else {
+            core::ptr::null()
+        };
+
+        DELETE_REQUESTED.store(false, Ordering::Relaxed);
+        nbgl_useCaseHomeAndSettings(
+            "Ironfish\0".as_ptr() as *const c_char,
+            &icon,
+            core::ptr::null(),
+            INIT_HOME_PAGE as u8,
+            &settings,
+            &infos,
+            action,
+            Some(quit_callback),
+        );
+
+        // The screen stays up while touch events are processed, until the user
+        // quits, asks to delete the account or an APDU is received
+        loop {
+            if QUIT_REQUESTED.load(Ordering::Relaxed) {
+                ledger_device_sdk::exit_app(0);
+            }
+            if DELETE_REQUESTED.load(Ordering::Relaxed) {
+                return MenuEvent::DeleteAccount;
+            }
+            if let Event::Command(ins) = comm.next_event::<Instruction>() {
+                return MenuEvent::Command(ins);
+            }
+        }
+    }
+}
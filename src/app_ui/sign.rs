@@ -0,0 +1,313 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::AppSW;
+use crate::ironfish::address::encode_address;
+use crate::ironfish::assets::{display_amount, AssetDescriptor};
+use crate::ironfish::constants::NATIVE_ASSET;
+use crate::ironfish::note::Note;
+use crate::ironfish::transaction::Transaction;
+use ironfish_frost::frost::SigningPackage;
+
+#[cfg(not(any(target_os = "stax", target_os = "flex")))]
+use ledger_device_sdk::ui::{
+    bitmaps::{CROSSMARK, EYE, VALIDATE_14, WARNING},
+    gadgets::{Field, MultiFieldReview},
+};
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use include_gif::include_gif;
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use ledger_device_sdk::nbgl::{Field, NbglGlyph, NbglReview};
+
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+const APP_ICON: NbglGlyph = NbglGlyph::from_include(include_gif!("stax_icon.gif", NBGL));
+
+/// Push the fields describing an amount of a given asset. Assets without verified
+/// metadata get an extra field with the raw identifier, as a warning.
+fn push_amount(
+    fields: &mut Vec<(String, String)>,
+    name: String,
+    asset_id: &[u8; 32],
+    value: u64,
+    assets: &[AssetDescriptor],
+) {
+    let (amount, known) = display_amount(asset_id, value, assets);
+    if !known {
+        fields.push((format!("{} WARNING", name), String::from("Unknown asset")));
+        fields.push((format!("{} asset", name), hex::encode(asset_id)));
+    }
+    fields.push((name, amount));
+}
+
+/// Asset names and metadata are zero padded. Printable ones are shown as text,
+/// anything else as hex.
+fn display_bytes(bytes: &[u8]) -> String {
+    let end = bytes.iter().rposition(|b| *b != 0).map_or(0, |pos| pos + 1);
+    let trimmed = &bytes[..end];
+
+    if trimmed.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        if let Ok(text) = core::str::from_utf8(trimmed) {
+            return String::from(text);
+        }
+    }
+
+    hex::encode(trimmed)
+}
+
+#[inline(never)]
+fn transaction_fields(tx: &Transaction, outputs: &[Note], assets: &[AssetDescriptor]) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    for (i, output) in outputs.iter().enumerate() {
+        fields.push((format!("Output {} to", i + 1), encode_address(&output.owner)));
+        push_amount(&mut fields, format!("Output {}", i + 1), &output.asset_id, output.value, assets);
+    }
+
+    for (i, mint) in tx.mints.iter().enumerate() {
+        fields.push((format!("Mint {} name", i + 1), display_bytes(mint.name)));
+        fields.push((format!("Mint {} metadata", i + 1), display_bytes(mint.metadata)));
+        push_amount(&mut fields, format!("Mint {}", i + 1), &mint.asset_id, mint.value, assets);
+        if let Some(new_owner) = mint.transfer_ownership_to.as_ref() {
+            fields.push((format!("Mint {} new owner", i + 1), encode_address(new_owner)));
+        }
+    }
+
+    for (i, burn) in tx.burns.iter().enumerate() {
+        fields.push((format!("Burn {} asset", i + 1), hex::encode(burn.asset_id)));
+        push_amount(&mut fields, format!("Burn {}", i + 1), &burn.asset_id, burn.value, assets);
+    }
+
+    // The fee is always paid in the native asset
+    push_amount(&mut fields, String::from("Fee"), &NATIVE_ASSET, tx.fee as u64, assets);
+
+    fields
+}
+
+/// Signing details only shown in expert mode: the randomizer and the commitments
+/// of every signer taking part.
+#[inline(never)]
+pub fn expert_fields(randomizer: &[u8], signing_package: &SigningPackage) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    fields.push((String::from("Randomizer"), hex::encode(randomizer)));
+    for (i, commitments) in signing_package.signing_commitments().values().enumerate() {
+        if let Ok(commitments) = commitments.serialize() {
+            fields.push((format!("Commitments {}", i + 1), hex::encode(commitments)));
+        }
+    }
+
+    fields
+}
+
+/// Transferring the ownership of an asset cannot be undone, so it requires an
+/// explicit confirmation on top of the transaction review.
+#[inline(never)]
+fn ui_confirm_ownership_transfer(mint_index: usize, asset_name: &[u8], new_owner: &[u8; 32]) -> bool {
+    let asset = display_bytes(asset_name);
+    let owner = encode_address(new_owner);
+    let mint = format!("Mint {}", mint_index + 1);
+
+    let fields = [
+        Field { name: "Transfer of", value: mint.as_str() },
+        Field { name: "Asset", value: asset.as_str() },
+        Field { name: "New owner", value: owner.as_str() },
+        Field { name: "Warning", value: "This cannot be undone" },
+    ];
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    {
+        let review = MultiFieldReview::new(
+            &fields,
+            &["Confirm mint", "ownership transfer"],
+            Some(&WARNING),
+            "Confirm transfer",
+            Some(&VALIDATE_14),
+            "Reject",
+            Some(&CROSSMARK),
+        );
+
+        review.show()
+    }
+
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    {
+        let mut review = NbglReview::new()
+            .titles("Confirm mint\nownership transfer", "This cannot be undone", "Confirm transfer")
+            .glyph(&APP_ICON);
+
+        review.show(&fields)
+    }
+}
+
+/// Displays the transaction being signed and lets the user approve or reject it.
+///
+/// # Arguments
+///
+/// * `tx` - Transaction to be displayed for validation
+/// * `outputs` - Notes of the outputs, checked against their commitments, or empty
+/// * `assets` - Asset descriptors provided by the host for this transaction
+/// * `details` - Extra fields shown after the transaction, empty unless in expert mode
+///
+/// # Returns
+///
+/// * `Ok(true)` if the user approves, `Ok(false)` if the user rejects
+#[inline(never)]
+pub fn ui_review_transaction(
+    tx: &Transaction,
+    outputs: &[Note],
+    assets: &[AssetDescriptor],
+    details: &[(String, String)],
+) -> Result<bool, AppSW> {
+    if tx.fee < 0 {
+        return Err(AppSW::TxDisplayFail);
+    }
+
+    let values = transaction_fields(tx, outputs, assets);
+    let fields: Vec<Field> = values
+        .iter()
+        .chain(details.iter())
+        .map(|(name, value)| Field { name: name.as_str(), value: value.as_str() })
+        .collect();
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    let approved = {
+        let review = MultiFieldReview::new(
+            &fields,
+            &["Review", "Transaction"],
+            Some(&EYE),
+            "Approve",
+            Some(&VALIDATE_14),
+            "Reject",
+            Some(&CROSSMARK),
+        );
+
+        review.show()
+    };
+
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    let approved = {
+        let mut review = NbglReview::new()
+            .titles("Review transaction", "", "Sign transaction")
+            .glyph(&APP_ICON);
+
+        review.show(&fields)
+    };
+
+    if !approved {
+        return Ok(false);
+    }
+
+    for (i, mint) in tx.mints.iter().enumerate() {
+        if let Some(new_owner) = mint.transfer_ownership_to.as_ref() {
+            if !ui_confirm_ownership_transfer(i, mint.name, new_owner) {
+                return Ok(false);
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+/// Warns the user that the request cannot be interpreted by the device and shows
+/// the only thing that can be checked, the hash being signed.
+///
+/// # Arguments
+///
+/// * `hash` - Message to be signed, as received from the host
+/// * `details` - Extra fields shown after the hash, empty unless in expert mode
+///
+/// # Returns
+///
+/// * `true` if the user approves, `false` if the user rejects
+#[inline(never)]
+pub fn ui_review_blind_signing(hash: &[u8], details: &[(String, String)]) -> bool {
+    let hash = hex::encode(hash);
+    let mut fields = Vec::new();
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    fields.push(Field { name: "Warning", value: "Transaction details cannot be verified" });
+    fields.push(Field { name: "Hash", value: hash.as_str() });
+    for (name, value) in details.iter() {
+        fields.push(Field { name: name.as_str(), value: value.as_str() });
+    }
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    {
+        let review = MultiFieldReview::new(
+            &fields,
+            &["Blind", "signing"],
+            Some(&WARNING),
+            "Accept risk and sign",
+            Some(&VALIDATE_14),
+            "Reject",
+            Some(&CROSSMARK),
+        );
+
+        review.show()
+    }
+
+    // The warning is the subtitle of the first page
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    {
+        let mut review = NbglReview::new()
+            .titles("Blind signing", "Transaction details cannot be verified", "Accept risk and sign")
+            .glyph(&APP_ICON);
+
+        review.show(&fields)
+    }
+}
+
+/// Displays the fingerprint of the key about to be trusted for asset descriptors.
+/// Assets it describes are then shown with their ticker and decimals.
+///
+/// # Returns
+///
+/// * `true` if the user approves, `false` if the user rejects
+#[inline(never)]
+pub fn ui_confirm_asset_key(fingerprint: &str) -> bool {
+    let fields = [
+        Field { name: "Fingerprint", value: fingerprint },
+        Field { name: "Warning", value: "Check it matches the published one" },
+    ];
+
+    #[cfg(not(any(target_os = "stax", target_os = "flex")))]
+    {
+        let review = MultiFieldReview::new(
+            &fields,
+            &["Install", "asset list key"],
+            Some(&WARNING),
+            "Approve",
+            Some(&VALIDATE_14),
+            "Reject",
+            Some(&CROSSMARK),
+        );
+
+        review.show()
+    }
+
+    #[cfg(any(target_os = "stax", target_os = "flex"))]
+    {
+        let mut review = NbglReview::new()
+            .titles("Install asset list key", "Asset names and decimals will be trusted", "Install key")
+            .glyph(&APP_ICON);
+
+        review.show(&fields)
+    }
+}
@@ -1,8 +1,12 @@
+use alloc::vec::Vec;
+use crate::ironfish::assets::AssetDescriptor;
 use crate::nvm::buffer::Buffer;
 
 pub struct TxContext {
     pub buffer: Buffer,
-    pub done: bool
+    pub done: bool,
+    // Verified asset descriptors, kept across resets until the next signature
+    pub assets: Vec<AssetDescriptor>,
 }
 
 // Implement constructor for TxInfo with default values
@@ -11,7 +15,8 @@ impl TxContext {
     pub fn new() -> TxContext {
         TxContext {
             buffer: Buffer::new(),
-            done: false
+            done: false,
+            assets: Vec::new(),
         }
     }
 
@@ -0,0 +1,193 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::{AppSW, Instruction};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use ironfish_frost::frost::{self, frost::verify_signature_share, RandomizedParams, Randomizer, SigningPackage};
+use ironfish_frost::frost::round2::SignatureShare;
+use ironfish_frost::participant::{Identity, IDENTITY_LEN};
+use ledger_device_sdk::io::{Comm, Event};
+use crate::accumulator::accumulate_data;
+use crate::context::TxContext;
+use crate::nvm::buffer::Buffer;
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::utils::zlog_stack;
+
+const MAX_APDU_SIZE: usize = 253;
+
+/// First byte of the response
+const AGGREGATE_OK: u8 = 0;
+const AGGREGATE_INVALID_SHARES: u8 = 1;
+
+pub struct Tx {
+    randomizer: Randomizer,
+    signing_package: SigningPackage,
+    shares: Vec<(Identity, SignatureShare)>,
+}
+
+/// Aggregates the signature shares of the participants of the stored account, so a
+/// coordinator device can check the final signature before it is broadcast.
+///
+/// Every share is checked against the verifying share of its participant first. The
+/// response is `0 | signature` when the signature is valid for the randomized group
+/// key, or `1 | n | identities` of the participants whose share is invalid.
+#[inline(never)]
+pub fn handler_dkg_aggregate(
+    comm: &mut Comm,
+    chunk: u8,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_aggregate\0");
+
+    accumulate_data(comm, chunk, ctx)?;
+    if !ctx.done {
+        return Ok(());
+    }
+
+    let tx = parse_tx(&ctx.buffer)?;
+
+    let resp = aggregate(tx)?;
+
+    send_apdu_chunks(comm, resp.as_slice())
+}
+
+#[inline(never)]
+fn parse_tx(buffer: &Buffer) -> Result<Tx, AppSW>{
+    zlog_stack("start parse_tx aggregate\0");
+
+    let mut tx_pos = 0;
+
+    let len = buffer.get_u16(tx_pos)?;
+    tx_pos += 2;
+
+    let data = buffer.get_slice(tx_pos, tx_pos+len)?;
+    let randomizer = Randomizer::deserialize(data).map_err(|_| AppSW::InvalidRandomizer)?;
+    tx_pos += len;
+
+    let len = buffer.get_u16(tx_pos)?;
+    tx_pos += 2;
+
+    let data = buffer.get_slice(tx_pos, tx_pos+len)?;
+    let signing_package = SigningPackage::deserialize(data).map_err(|_| AppSW::InvalidSigningPackage)?;
+    tx_pos += len;
+
+    let elements = buffer.get_element(tx_pos)?;
+    tx_pos += 1;
+
+    let mut shares = Vec::with_capacity(elements as usize);
+    for _i in 0..elements {
+        let data = buffer.get_slice(tx_pos, tx_pos+IDENTITY_LEN)?;
+        let identity = Identity::deserialize_from(data).map_err(|_| AppSW::InvalidIdentity)?;
+        tx_pos += IDENTITY_LEN;
+
+        let len = buffer.get_u16(tx_pos)?;
+        tx_pos += 2;
+
+        let data = buffer.get_slice(tx_pos, tx_pos+len)?;
+        let share = SignatureShare::deserialize(data).map_err(|_| AppSW::InvalidSignatureShare)?;
+        tx_pos += len;
+
+        shares.push((identity, share));
+    }
+
+    if tx_pos != buffer.pos {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    Ok(Tx{randomizer, signing_package, shares})
+}
+
+#[inline(never)]
+fn aggregate(tx: Tx) -> Result<Vec<u8>, AppSW> {
+    zlog_stack("start aggregate\0");
+
+    let identities = DkgKeys.load_identities()?;
+    let public_key_package = DkgKeys.load_frost_public_key_package()?;
+    let randomized_params = RandomizedParams::from_randomizer(public_key_package.verifying_key(), tx.randomizer);
+
+    // One share for each participant that committed to sign, and no one else
+    if tx.shares.len() != tx.signing_package.signing_commitments().len() {
+        return Err(AppSW::InvalidSignatureShare);
+    }
+
+    let mut signature_shares = BTreeMap::new();
+    let mut culprits: Vec<&Identity> = Vec::new();
+    for (identity, share) in tx.shares.iter() {
+        let identifier = identity.to_frost_identifier();
+        if !identities.contains(identity)
+            || !tx.signing_package.signing_commitments().contains_key(&identifier)
+            || signature_shares.contains_key(&identifier)
+        {
+            return Err(AppSW::InvalidSignatureShare);
+        }
+
+        let verifying_share = public_key_package.verifying_shares().get(&identifier)
+            .ok_or(AppSW::InvalidPublicPackage)?;
+        if verify_signature_share(
+            identifier,
+            verifying_share,
+            share,
+            &tx.signing_package,
+            randomized_params.randomized_verifying_key(),
+        ).is_err() {
+            culprits.push(identity);
+        }
+
+        signature_shares.insert(identifier, *share);
+    }
+
+    let mut resp: Vec<u8> = Vec::new();
+    if !culprits.is_empty() {
+        resp.push(AGGREGATE_INVALID_SHARES);
+        resp.push(culprits.len() as u8);
+        for identity in culprits.iter() {
+            resp.extend_from_slice(identity.serialize().as_ref());
+        }
+        return Ok(resp);
+    }
+
+    let signature = frost::aggregate(&tx.signing_package, &signature_shares, &public_key_package, &randomized_params)
+        .map_err(|_| AppSW::InvalidSignatureShare)?;
+
+    randomized_params.randomized_verifying_key()
+        .verify(tx.signing_package.message(), &signature)
+        .map_err(|_| AppSW::InvalidSignatureShare)?;
+
+    resp.push(AGGREGATE_OK);
+    resp.extend_from_slice(&signature.serialize().map_err(|_| AppSW::InvalidSignatureShare)?);
+
+    Ok(resp)
+}
+
+fn send_apdu_chunks(comm: &mut Comm, data: &[u8]) -> Result<(), AppSW> {
+    let total_chunks = (data.len() + MAX_APDU_SIZE - 1) / MAX_APDU_SIZE;
+
+    for (i, chunk) in data.chunks(MAX_APDU_SIZE).enumerate() {
+        comm.append(chunk);
+
+        if i < total_chunks - 1 {
+            comm.reply_ok();
+            match comm.next_event() {
+                Event::Command(Instruction::DkgAggregate { chunk: 0 }) => {}
+                _ => {},
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -22,17 +22,13 @@ use ironfish_frost::signing_commitment;
 use ledger_device_sdk::io::{Comm, Event};
 use serde::Serialize;
 use crate::accumulator::accumulate_data;
-use crate::nvm::buffer::{Buffer};
 use crate::context::TxContext;
 use crate::utils::{zlog_stack};
-use ironfish_frost::nonces::deterministic_signing_nonces;
+use crate::handlers::dkg_nonces::{entry_signing_nonces, parse_tx};
 use ironfish_frost::frost::round1::SigningCommitments;
-use ironfish_frost::participant::Identity;
 use crate::nvm::dkg_keys::DkgKeys;
 
 const MAX_APDU_SIZE: usize = 253;
-const IDENTITY_LEN: usize = 129;
-const TX_HASH_LEN: usize = 32;
 
 #[inline(never)]
 pub fn handler_dkg_commitments(
@@ -47,14 +43,10 @@ pub fn handler_dkg_commitments(
         return Ok(());
     }
 
-    let (identities, tx_hash) = parse_tx(&ctx.buffer)?;
+    let (identities, tx_hash, entry) = parse_tx(&ctx.buffer)?;
     let key_package = DkgKeys.load_key_package()?;
 
-    let nonces = deterministic_signing_nonces(
-        key_package.signing_share(),
-        tx_hash,
-        &identities,
-    );
+    let nonces = entry_signing_nonces(key_package.signing_share(), tx_hash, &identities, entry);
 
     let signing_commitment:SigningCommitments  = (&nonces).into();
     let ser = signing_commitment.serialize().unwrap();
@@ -63,34 +55,6 @@ pub fn handler_dkg_commitments(
 }
 
 
-#[inline(never)]
-fn parse_tx(buffer: &Buffer) -> Result<(Vec<Identity>, &[u8]), AppSW>{
-    zlog_stack("start parse_tx\0");
-
-    let mut tx_pos = 0;
-    let elements = buffer.get_element(tx_pos)?;
-    tx_pos +=1;
-
-    let mut identities:Vec<Identity> = Vec::with_capacity(elements as usize);
-    for _i in 0..elements {
-        let data = buffer.get_slice(tx_pos,tx_pos+IDENTITY_LEN)?;
-        let identity = Identity::deserialize_from(data).map_err(|_| AppSW::InvalidIdentity)?;
-        tx_pos += IDENTITY_LEN;
-
-        identities.push(identity);
-    }
-
-
-    let tx_hash = buffer.get_slice(tx_pos, tx_pos + TX_HASH_LEN)?;
-    tx_pos += TX_HASH_LEN;
-
-    if tx_pos != buffer.pos {
-        return Err(AppSW::InvalidPayload);
-    }
-
-    Ok((identities, tx_hash))
-}
-
 #[inline(never)]
 fn send_apdu_chunks(comm: &mut Comm, data_vec: Vec<u8>) -> Result<(), AppSW> {
     zlog_stack("start send_apdu_chunks\0");
@@ -0,0 +1,65 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::AppSW;
+use ledger_device_sdk::io::Comm;
+use crate::ironfish::note::{Note, NOTE_COMMITMENT_SIZE};
+use crate::ironfish::transaction::ENCRYPTED_NOTE_WITH_MAC_SIZE;
+use crate::ironfish::shares::{point_from_bytes, POINT_LEN};
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::utils::zlog_stack;
+
+/// Decrypts a note sent to the stored account, so the host can scan for incoming
+/// notes without holding the incoming viewing key.
+///
+/// The payload is `ephemeral public key | note commitment | encrypted note`, as found
+/// in the output description, the encrypted note being followed by its MAC. The
+/// response is `value (u64 LE) | asset id | memo | sender`, NoteNotOwned if the note
+/// was not sent to this account, or OutputCommitmentMismatch if the decrypted note
+/// is not the one the output commits to.
+#[inline(never)]
+pub fn handler_dkg_decrypt_note(comm: &mut Comm) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_decrypt_note\0");
+
+    let data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
+    if data.len() != POINT_LEN + NOTE_COMMITMENT_SIZE + ENCRYPTED_NOTE_WITH_MAC_SIZE {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    let ephemeral_public_key = point_from_bytes(&data[..POINT_LEN]).map_err(|_| AppSW::InvalidNote)?;
+    let note_commitment = &data[POINT_LEN..POINT_LEN + NOTE_COMMITMENT_SIZE];
+    let encrypted_note = &data[POINT_LEN + NOTE_COMMITMENT_SIZE..];
+
+    let account_keys = DkgKeys.load_account_keys()?;
+
+    let shared_secret = account_keys.incoming_viewing_key.shared_secret(&ephemeral_public_key);
+    let note = Note::from_owner_encrypted(&account_keys.incoming_viewing_key, &shared_secret, encrypted_note)
+        .map_err(|_| AppSW::NoteNotOwned)?;
+    drop(account_keys);
+
+    // The plaintext is authenticated by the sender only, the commitment binds it to the output
+    if note.commitment().map_err(|_| AppSW::InvalidNote)?[..] != *note_commitment {
+        return Err(AppSW::OutputCommitmentMismatch);
+    }
+
+    comm.append(&note.value.to_le_bytes());
+    comm.append(&note.asset_id);
+    comm.append(&note.memo);
+    comm.append(&note.sender);
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::AppSW;
+use ledger_device_sdk::io::Comm;
+use crate::app_ui::dkg::ui_confirm_account_deletion;
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::nvm::dkg_secrets::DkgSecrets;
+use crate::utils::zlog_stack;
+
+#[inline(never)]
+pub fn handler_dkg_delete_account(_comm: &mut Comm) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_delete_account\0");
+
+    delete_account()
+}
+
+/// Erases the stored account, and whatever a ceremony in progress kept on the
+/// device, once the user confirmed the address being removed. Also reachable from
+/// the device menu.
+#[inline(never)]
+pub fn delete_account() -> Result<(), AppSW> {
+    if !DkgKeys.has_account() {
+        return Err(AppSW::AccountNotFound);
+    }
+
+    let address = DkgKeys.load_public_address()?;
+    if !ui_confirm_account_deletion(&address) {
+        return Err(AppSW::Deny);
+    }
+
+    DkgKeys.clear();
+    DkgSecrets.clear();
+
+    Ok(())
+}
@@ -19,21 +19,33 @@ use alloc::vec;
 use alloc::vec::Vec;
 use ironfish_frost::participant::Secret as ironfishSecret;
 use crate::AppSW;
+use crate::nvm::buffer::Buffer;
+use crate::utils::Bip32Path;
 use ledger_device_sdk::ecc::{Secret, bip32_derive, CurvesId, ChainCode};
 use ledger_device_sdk::io::Comm;
 
-const MAX_IDENTITY_INDEX:u8 = 5;
+/// P2 flag of the DKG rounds, set when the identity is given as a BIP32 path
+/// instead of a legacy index
+pub const IDENTITY_AS_PATH: u8 = 0x01;
+
+const HARDENED: u32 = 0x80000000;
+/// Identities are derived under the path declared in Cargo.toml, 44'/1338'
+const IDENTITY_PATH_PREFIX: [u32; 2] = [HARDENED | 0x2c, HARDENED | 0x53a];
+const MAX_IDENTITY_PATH_LEN: usize = 10;
 
 #[inline(never)]
 pub fn handler_dkg_get_identity(comm: &mut Comm) -> Result<(), AppSW> {
     let data_vec = comm.get_data().map_err(|_| AppSW::WrongApduLength)?.to_vec();
     let data = data_vec.as_slice();
 
-    if data.len() != 1 || data[0] > MAX_IDENTITY_INDEX{
-        return Err(AppSW::InvalidIdentityIndex);
-    }
+    // A single byte is a legacy index, anything else a serialized path
+    let path = match data.len() {
+        0 => return Err(AppSW::InvalidIdentityIndex),
+        1 => IdentityPath::Legacy(data[0]),
+        _ => parse_identity_path(data)?,
+    };
 
-    let secret = compute_dkg_secret(data[0]);
+    let secret = compute_dkg_secret(&path);
     let identity = secret.to_identity();
 
     comm.append(identity.serialize().as_ref());
@@ -41,15 +53,99 @@ pub fn handler_dkg_get_identity(comm: &mut Comm) -> Result<(), AppSW> {
     Ok(())
 }
 
+/// Where the two keys of an identity are derived
+pub enum IdentityPath {
+    /// Legacy index form: 44'/1338'/0'/0'/(2 * index)' and the next index
+    Legacy(u8),
+    /// Path given by the host, the keys are its children 0' and 1'
+    Path(Bip32Path),
+}
+
+impl IdentityPath {
+    fn key_paths(&self) -> (Vec<u32>, Vec<u32>) {
+        match self {
+            IdentityPath::Legacy(index) => {
+                let key_0 = legacy_key_path(*index as u32 * 2);
+                let key_1 = legacy_key_path(*index as u32 * 2 + 1);
+                (key_0, key_1)
+            }
+            IdentityPath::Path(path) => {
+                let mut key_0 = path.as_ref().to_vec();
+                let mut key_1 = key_0.clone();
+                key_0.push(HARDENED);
+                key_1.push(HARDENED | 1);
+                (key_0, key_1)
+            }
+        }
+    }
+}
+
+fn legacy_key_path(index: u32) -> Vec<u32> {
+    vec![
+        IDENTITY_PATH_PREFIX[0],
+        IDENTITY_PATH_PREFIX[1],
+        HARDENED,
+        HARDENED,
+        HARDENED | index,
+    ]
+}
+
+/// Parse a path (length byte followed by big endian components) and check it can be
+/// used for an identity: under 44'/1338', fully hardened, and with room for the
+/// child component each key is derived at.
+pub fn parse_identity_path(data: &[u8]) -> Result<IdentityPath, AppSW> {
+    let path = Bip32Path::try_from(data).map_err(|_| AppSW::InvalidIdentityPath)?;
+    let components = path.as_ref();
+
+    if components.len() <= IDENTITY_PATH_PREFIX.len()
+        || components.len() >= MAX_IDENTITY_PATH_LEN
+        || components[..IDENTITY_PATH_PREFIX.len()] != IDENTITY_PATH_PREFIX
+        || components.iter().any(|c| c & HARDENED == 0)
+    {
+        return Err(AppSW::InvalidIdentityPath);
+    }
+
+    Ok(IdentityPath::Path(path))
+}
+
+/// Read the identity a DKG round runs for, at the given position of the payload.
+/// Returns the path and the position right after it.
+pub fn read_identity_path(buffer: &Buffer, tx_pos: usize, flags: u8) -> Result<(IdentityPath, usize), AppSW> {
+    let elements = buffer.get_element(tx_pos)? as usize;
+
+    if flags & IDENTITY_AS_PATH == 0 {
+        return Ok((IdentityPath::Legacy(elements as u8), tx_pos + 1));
+    }
+
+    let end = tx_pos + 1 + elements * 4;
+    let path = parse_identity_path(buffer.get_slice(tx_pos, end)?)?;
+
+    Ok((path, end))
+}
+
+/// Same as [`read_identity_path`] for data received in a single APDU. Returns the
+/// path and the rest of the data.
+pub fn split_identity_path(data: &[u8], flags: u8) -> Result<(IdentityPath, &[u8]), AppSW> {
+    let elements = *data.first().ok_or(AppSW::InvalidIdentityIndex)? as usize;
+
+    if flags & IDENTITY_AS_PATH == 0 {
+        return Ok((IdentityPath::Legacy(elements as u8), &data[1..]));
+    }
+
+    let end = 1 + elements * 4;
+    if data.len() < end {
+        return Err(AppSW::InvalidIdentityPath);
+    }
+    let path = parse_identity_path(&data[..end])?;
+
+    Ok((path, &data[end..]))
+}
+
+/// The identity is made of two keys, derived where [`IdentityPath::key_paths`] says.
+/// A host path must have been checked with [`parse_identity_path`].
 #[inline(never)]
-pub fn compute_dkg_secret(index: u8) -> ironfishSecret {
-    let index_1 = (index * 2) as u32;
-    let index_2 = index_1 + 1;
-
-    let path_0: Vec<u32> = vec![(0x80000000 | 0x2c), (0x80000000 | 0x53a), (0x80000000 | 0x0), (0x80000000 | 0x0),
-                                (0x80000000 | index_1)];
-    let path_1: Vec<u32> = vec![(0x80000000 | 0x2c), (0x80000000 | 0x53a), (0x80000000 | 0x0), (0x80000000 | 0x0),
-                                (0x80000000 | index_2)];
+pub fn compute_dkg_secret(path: &IdentityPath) -> ironfishSecret {
+    let (path_0, path_1) = path.key_paths();
 
     let mut secret_key_0 = Secret::<64>::new();
     let mut secret_key_1 = Secret::<64>::new();
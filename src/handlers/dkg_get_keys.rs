@@ -24,6 +24,8 @@ use ledger_device_sdk::io::{Comm, Event};
 use crate::ironfish::multisig::{derive_account_keys, MultisigAccountKeys};
 use crate::utils::{zlog_stack};
 use crate::nvm::dkg_keys::DkgKeys;
+use crate::nvm::settings::{Settings, KEY_EXPORT_APPROVAL};
+use crate::app_ui::keys::{ui_approve_key_export, ui_review_address};
 
 const MAX_APDU_SIZE: usize = 253;
 
@@ -42,14 +44,28 @@ pub fn handler_dkg_get_keys(
 
     let account_keys = derive_account_keys(verifying_key, &group_secret_key);
 
-    let resp = get_requested_keys(&account_keys, key_type)?;
+    let resp = get_requested_keys(&account_keys, verifying_key, key_type)?;
+
+    if Settings.is_enabled(KEY_EXPORT_APPROVAL) {
+        let address = account_keys.public_address.public_address();
+        let approved = match key_type {
+            0 => ui_review_address(&address),
+            1 => ui_approve_key_export("View keys", &address),
+            2 => ui_approve_key_export("Proof generation key", &address),
+            // Verifying keys are public, other participants hold them too
+            _ => true,
+        };
+        if !approved {
+            return Err(AppSW::Deny);
+        }
+    }
     drop(account_keys);
 
     send_apdu_chunks(comm, resp.as_slice())
 }
 
 #[inline(never)]
-fn get_requested_keys(account_keys: &MultisigAccountKeys, key_type: &u8) -> Result<Vec<u8>, AppSW>{
+fn get_requested_keys(account_keys: &MultisigAccountKeys, verifying_key: &[u8; 32], key_type: &u8) -> Result<Vec<u8>, AppSW>{
     zlog_stack("start get_requested_keys\0");
 
     let mut resp: Vec<u8> = Vec::with_capacity(32 * 4);
@@ -72,6 +88,16 @@ fn get_requested_keys(account_keys: &MultisigAccountKeys, key_type: &u8) -> Resu
             resp.extend_from_slice(account_keys.proof_authorizing_key.to_bytes().as_ref());
             Ok(resp)
         },
+        3 => {
+            resp.extend_from_slice(verifying_key);
+            Ok(resp)
+        },
+        4 => {
+            let key_package = DkgKeys.load_key_package()?;
+            let verifying_share = key_package.verifying_share().serialize().map_err(|_| AppSW::InvalidKeyPackage)?;
+            resp.extend_from_slice(verifying_share.as_ref());
+            Ok(resp)
+        },
         _ => Err(AppSW::InvalidKeyType)
     }
 }
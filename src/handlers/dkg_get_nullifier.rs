@@ -0,0 +1,54 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::AppSW;
+use ledger_device_sdk::io::Comm;
+use crate::ironfish::note::{Note, NOTE_SIZE};
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::utils::zlog_stack;
+
+const POSITION_LEN: usize = 8;
+
+/// Computes the nullifier of a note of the stored account, so the host can tell
+/// whether the note was spent without knowing the nullifier deriving key.
+///
+/// The payload is `position (u64 LE) | note`, the position being the one of the
+/// note commitment in the tree.
+#[inline(never)]
+pub fn handler_dkg_get_nullifier(comm: &mut Comm) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_get_nullifier\0");
+
+    let data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
+    if data.len() != POSITION_LEN + NOTE_SIZE {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    let mut position = [0u8; POSITION_LEN];
+    position.copy_from_slice(&data[..POSITION_LEN]);
+    let position = u64::from_le_bytes(position);
+    let note = Note::read(&data[POSITION_LEN..]).map_err(|_| AppSW::InvalidNote)?;
+
+    let account_keys = DkgKeys.load_account_keys()?;
+
+    let nullifier = note.nullifier(&account_keys.view_key.nullifier_deriving_key, position)
+        .map_err(|_| AppSW::InvalidNote)?;
+    drop(account_keys);
+
+    comm.append(&nullifier);
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::{AppSW, Instruction};
+use alloc::vec::Vec;
+use ledger_device_sdk::io::{Comm, Event};
+use crate::utils::{zlog_stack};
+use crate::nvm::dkg_keys::DkgKeys;
+
+const MAX_APDU_SIZE: usize = 253;
+
+/// Returns the participants of the stored account without the ironfish-frost
+/// encoding: `n | identities | min_signers | own index`, the index being the
+/// position of our identity in the list.
+#[inline(never)]
+pub fn handler_dkg_get_participants(
+    comm: &mut Comm
+) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_get_participants\0");
+
+    let identities = DkgKeys.load_identities()?;
+    let min_signers = DkgKeys.load_min_signers()?;
+    let key_package = DkgKeys.load_key_package()?;
+
+    let own_index = identities.iter()
+        .position(|identity| identity.to_frost_identifier() == *key_package.identifier())
+        .ok_or(AppSW::InvalidKeyPackage)?;
+    drop(key_package);
+
+    let mut resp: Vec<u8> = Vec::new();
+    resp.push(identities.len() as u8);
+    for identity in identities.iter() {
+        resp.extend_from_slice(identity.serialize().as_ref());
+    }
+    resp.push(min_signers as u8);
+    resp.push(own_index as u8);
+
+    send_apdu_chunks(comm, resp.as_slice())
+}
+
+#[inline(never)]
+fn send_apdu_chunks(comm: &mut Comm, data: &[u8]) -> Result<(), AppSW> {
+    zlog_stack("start send_apdu_chunks\0");
+
+    let total_chunks = (data.len() + MAX_APDU_SIZE - 1) / MAX_APDU_SIZE;
+
+    for (i, chunk) in data.chunks(MAX_APDU_SIZE).enumerate() {
+        comm.append(chunk);
+
+        if i < total_chunks - 1 {
+            comm.reply_ok();
+            match comm.next_event() {
+                Event::Command(Instruction::DkgGetParticipants) => {}
+                _ => {},
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::{AppSW};
+use alloc::vec::Vec;
+use ironfish_frost::multienc;
+use ironfish_frost::participant::Secret;
+use jubjub::{ExtendedPoint, Fr};
+use ledger_device_sdk::io::{Comm};
+use crate::accumulator::accumulate_data;
+use crate::app_ui::dkg::{ui_review_account, IMPORTED_ACCOUNT};
+use crate::context::TxContext;
+use crate::handlers::dkg_get_identity::{compute_dkg_secret, read_identity_path, IdentityPath};
+use crate::handlers::dkg_reshare_round_2::account_address;
+use crate::ironfish::shares::{generator_mul, identifier_to_scalar, lagrange_coefficient_at, point_from_bytes, scalar_from_bytes};
+use crate::nvm::buffer::Buffer;
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::nvm::dkg_secrets::StagedAccount;
+use crate::utils::zlog_stack;
+
+pub struct Tx {
+    identity_path: IdentityPath,
+    encrypted_account: Vec<u8>,
+}
+
+/// Imports an account whose shares were generated by a trusted dealer. The account
+/// is encrypted to one of our identities, with the layout of a staged account:
+/// `min_signers | n | identities | key package | public key package | gsk`.
+///
+/// The packages are checked to be consistent with each other and with our share
+/// before the user confirms the address.
+#[inline(never)]
+pub fn handler_dkg_import_account(
+    comm: &mut Comm,
+    chunk: u8,
+    flags: u8,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_import_account\0");
+
+    accumulate_data(comm, chunk, ctx)?;
+    if !ctx.done {
+        return Ok(());
+    }
+
+    let tx = parse_tx(&ctx.buffer, flags)?;
+    let secret = compute_dkg_secret(&tx.identity_path);
+
+    let mut plaintext = multienc::decrypt(&secret, &tx.encrypted_account)
+        .map_err(|_| AppSW::InvalidAccountImport)?;
+    drop(tx);
+    let account = StagedAccount::deserialize(&plaintext);
+    plaintext.fill(0);
+    let account = account?;
+
+    check_account(&secret, &account)?;
+
+    let address = account_address(&account)?;
+
+    if !ui_review_account(&IMPORTED_ACCOUNT, &address, account.min_signers, account.identities.len()) {
+        return Err(AppSW::Deny);
+    }
+
+    DkgKeys.save_account(
+        &account.identities,
+        account.min_signers,
+        &account.key_package,
+        &account.public_key_package,
+        &account.group_secret_key,
+    )
+}
+
+fn parse_tx(buffer: &Buffer, flags: u8) -> Result<Tx, AppSW>{
+    let (identity_path, mut tx_pos) = read_identity_path(buffer, 0, flags)?;
+
+    let len = buffer.get_u16(tx_pos)?;
+    tx_pos += 2;
+
+    let encrypted_account = buffer.get_slice(tx_pos, tx_pos+len)?.to_vec();
+    tx_pos += len;
+
+    if tx_pos != buffer.pos {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    Ok(Tx{identity_path, encrypted_account})
+}
+
+/// The key package must be ours and match the public key package, which must hold a
+/// verifying share for every participant. The verifying shares must be consistent
+/// with the threshold, and the verifying key must be the one they interpolate to.
+#[inline(never)]
+fn check_account(secret: &Secret, account: &StagedAccount) -> Result<(), AppSW> {
+    zlog_stack("start check_account\0");
+
+    let participants = account.identities.len();
+    if account.min_signers < 2 || account.min_signers as usize > participants {
+        return Err(AppSW::InvalidAccountImport);
+    }
+    for (i, identity) in account.identities.iter().enumerate() {
+        if account.identities[..i].contains(identity) {
+            return Err(AppSW::InvalidAccountImport);
+        }
+    }
+
+    let own_identifier = secret.to_identity().to_frost_identifier();
+    let key_package = &account.key_package;
+    let public_key_package = &account.public_key_package;
+
+    if *key_package.identifier() != own_identifier
+        || *key_package.min_signers() != account.min_signers as u16
+        || key_package.verifying_key() != public_key_package.verifying_key()
+        || public_key_package.verifying_shares().get(&own_identifier) != Some(key_package.verifying_share())
+    {
+        return Err(AppSW::InvalidKeyPackage);
+    }
+
+    let signing_share = scalar_from_bytes(key_package.signing_share().serialize().as_ref())
+        .map_err(|_| AppSW::InvalidKeyPackage)?;
+    let verifying_share = point_from_bytes(
+        key_package.verifying_share().serialize().map_err(|_| AppSW::InvalidKeyPackage)?.as_ref()
+    ).map_err(|_| AppSW::InvalidKeyPackage)?;
+    if generator_mul(&signing_share) != verifying_share {
+        return Err(AppSW::InvalidKeyPackage);
+    }
+
+    if public_key_package.verifying_shares().len() != participants {
+        return Err(AppSW::InvalidPublicPackage);
+    }
+
+    let mut xs: Vec<Fr> = Vec::with_capacity(participants);
+    let mut verifying_shares: Vec<ExtendedPoint> = Vec::with_capacity(participants);
+    for identity in account.identities.iter() {
+        let identifier = identity.to_frost_identifier();
+        let verifying_share = public_key_package.verifying_shares().get(&identifier)
+            .ok_or(AppSW::InvalidPublicPackage)?;
+        verifying_shares.push(point_from_bytes(
+            verifying_share.serialize().map_err(|_| AppSW::InvalidPublicPackage)?.as_ref()
+        ).map_err(|_| AppSW::InvalidPublicPackage)?);
+        xs.push(identifier_to_scalar(&identifier).map_err(|_| AppSW::InvalidPublicPackage)?);
+    }
+
+    // The verifying shares must all be on the same polynomial of degree
+    // min_signers - 1, so any min_signers participants can sign. The polynomial is
+    // the one going through the first ones, its value at zero is the verifying key.
+    let threshold = account.min_signers as usize;
+    let signers = &xs[..threshold];
+    let interpolate = |at: &Fr| -> Result<ExtendedPoint, AppSW> {
+        let mut result = ExtendedPoint::identity();
+        for (x, verifying_share) in signers.iter().zip(verifying_shares.iter()) {
+            let lambda = lagrange_coefficient_at(x, signers, at).map_err(|_| AppSW::InvalidAccountImport)?;
+            result += verifying_share * lambda;
+        }
+        Ok(result)
+    };
+
+    for (x, verifying_share) in xs.iter().zip(verifying_shares.iter()).skip(threshold) {
+        if interpolate(x)? != *verifying_share {
+            return Err(AppSW::InvalidAccountImport);
+        }
+    }
+
+    let verifying_key = point_from_bytes(
+        public_key_package.verifying_key().serialize().map_err(|_| AppSW::InvalidPublicPackage)?.as_ref()
+    ).map_err(|_| AppSW::InvalidPublicPackage)?;
+    if interpolate(&Fr::zero())? != verifying_key {
+        return Err(AppSW::InvalidAccountImport);
+    }
+
+    Ok(())
+}
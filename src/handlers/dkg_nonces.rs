@@ -17,6 +17,9 @@
 
 use crate::{AppSW, Instruction};
 use alloc::vec::Vec;
+use blake2b_simd::Params as Blake2b;
+use ironfish_frost::frost::keys::SigningShare;
+use ironfish_frost::frost::round1::SigningNonces;
 use ironfish_frost::frost::keys::KeyPackage;
 use ledger_device_sdk::io::{Comm, Event};
 use serde::Serialize;
@@ -31,6 +34,7 @@ use crate::nvm::dkg_keys::DkgKeys;
 const MAX_APDU_SIZE: usize = 253;
 const IDENTITY_LEN: usize = 129;
 const TX_HASH_LEN: usize = 32;
+const BATCH_ENTRY_PERSONALIZATION: &[u8; 16] = b"IFBatchNonceSeed";
 
 #[inline(never)]
 pub fn handler_dkg_nonces(
@@ -45,22 +49,44 @@ pub fn handler_dkg_nonces(
         return Ok(());
     }
 
-    let (identities, tx_hash) = parse_tx(&ctx.buffer)?;
+    let (identities, tx_hash, entry) = parse_tx(&ctx.buffer)?;
     let key_package = DkgKeys.load_key_package()?;
 
-    let nonces = deterministic_signing_nonces(
-        key_package.signing_share(),
-        tx_hash,
-        &identities,
-    );
+    let nonces = entry_signing_nonces(key_package.signing_share(), tx_hash, &identities, entry);
 
     let ser = nonces.serialize().unwrap();
 
     send_apdu_chunks(comm, ser)
 }
 
+/// Nonces for a signature of the transaction. Each entry of a batch signs the same
+/// transaction hash with another randomizer, so the entry index is mixed in to give
+/// every entry its own nonces.
+pub fn entry_signing_nonces(
+    signing_share: &SigningShare,
+    tx_hash: &[u8],
+    identities: &[Identity],
+    entry: Option<u8>,
+) -> SigningNonces {
+    match entry {
+        None => deterministic_signing_nonces(signing_share, tx_hash, identities),
+        Some(index) => {
+            let seed = Blake2b::new()
+                .hash_length(TX_HASH_LEN)
+                .personal(BATCH_ENTRY_PERSONALIZATION)
+                .to_state()
+                .update(tx_hash)
+                .update(&[index])
+                .finalize();
+            deterministic_signing_nonces(signing_share, seed.as_bytes(), identities)
+        }
+    }
+}
+
+/// Reads `n | identity * n | tx hash`, optionally followed by the index of the batch
+/// entry the nonces are for
 #[inline(never)]
-fn parse_tx(buffer: &Buffer) -> Result<(Vec<Identity>, &[u8]), AppSW>{
+pub fn parse_tx(buffer: &Buffer) -> Result<(Vec<Identity>, &[u8], Option<u8>), AppSW>{
     zlog_stack("start parse_tx\0");
 
     let mut tx_pos = 0;
@@ -80,11 +106,18 @@ fn parse_tx(buffer: &Buffer) -> Result<(Vec<Identity>, &[u8]), AppSW>{
     let tx_hash = buffer.get_slice(tx_pos, tx_pos + TX_HASH_LEN)?;
     tx_pos += TX_HASH_LEN;
 
+    let entry = if tx_pos < buffer.pos {
+        tx_pos += 1;
+        Some(buffer.get_element(tx_pos - 1)?)
+    } else {
+        None
+    };
+
     if tx_pos != buffer.pos {
         return Err(AppSW::InvalidPayload);
     }
 
-    Ok((identities, tx_hash))
+    Ok((identities, tx_hash, entry))
 }
 
 #[inline(never)]
@@ -0,0 +1,58 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use alloc::vec::Vec;
+use ed25519_dalek::Signer;
+use crate::AppSW;
+use crate::handlers::dkg_get_identity::{compute_dkg_secret, split_identity_path};
+use crate::ironfish::constants::IDENTITY_PROOF_DOMAIN;
+use crate::utils::zlog_stack;
+use ledger_device_sdk::io::Comm;
+
+const MIN_CHALLENGE_LEN: usize = 16;
+const MAX_CHALLENGE_LEN: usize = 128;
+
+/// Signs a challenge chosen by another participant with the signing key of one of
+/// our identities, so they can check the identity belongs to this device before
+/// using it in a ceremony.
+///
+/// The signed message is `IDENTITY_PROOF_DOMAIN | identity (129) | challenge`, which
+/// can never be confused with the data signed by the identity itself.
+#[inline(never)]
+pub fn handler_dkg_prove_identity(comm: &mut Comm, flags: u8) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_prove_identity\0");
+
+    let data_vec = comm.get_data().map_err(|_| AppSW::WrongApduLength)?.to_vec();
+    let (path, challenge) = split_identity_path(data_vec.as_slice(), flags)?;
+
+    if challenge.len() < MIN_CHALLENGE_LEN || challenge.len() > MAX_CHALLENGE_LEN {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    let secret = compute_dkg_secret(&path);
+    let identity = secret.to_identity();
+
+    let mut message: Vec<u8> = Vec::new();
+    message.extend_from_slice(IDENTITY_PROOF_DOMAIN);
+    message.extend_from_slice(identity.serialize().as_ref());
+    message.extend_from_slice(challenge);
+
+    let signature = secret.signing_key().sign(&message);
+    comm.append(&signature.to_bytes());
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::{AppSW, Instruction};
+use alloc::vec::Vec;
+use ironfish_frost::multienc;
+use ironfish_frost::participant::Secret;
+use jubjub::Fr;
+use ledger_device_sdk::io::{Comm, Event};
+use ledger_device_sdk::random::{rand_bytes, LedgerRng};
+use crate::app_ui::dkg::{ui_review_account, REFRESH};
+use crate::handlers::dkg_get_identity::{compute_dkg_secret, split_identity_path};
+use crate::ironfish::shares::{identifier_to_scalar, point_to_bytes, Polynomial};
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::utils::zlog_stack;
+
+const MAX_APDU_SIZE: usize = 253;
+
+/// First round of a share refresh, run by every participant of the stored account.
+/// Each one deals shares of zero to all the participants, so adding them to the
+/// current shares gives new shares of the same account key.
+///
+/// The response has the layout of a reshare dealer package, without the commitment
+/// to the constant term, which is always zero: `identity | min_signers - 1 |
+/// commitments | n | encrypted share for each participant`, in the order of the
+/// stored identities.
+#[inline(never)]
+pub fn handler_dkg_refresh_round_1(comm: &mut Comm, flags: u8) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_refresh_round_1\0");
+
+    let data_vec = comm.get_data().map_err(|_| AppSW::WrongApduLength)?.to_vec();
+    let (path, rest) = split_identity_path(data_vec.as_slice(), flags)?;
+    if !rest.is_empty() {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    let secret = compute_dkg_secret(&path);
+
+    let identities = DkgKeys.load_identities()?;
+    let min_signers = DkgKeys.load_min_signers()? as u8;
+    if !identities.contains(&secret.to_identity()) {
+        return Err(AppSW::DkgRefreshFail);
+    }
+
+    let address = DkgKeys.load_public_address()?;
+
+    if !ui_review_account(&REFRESH, &address, min_signers, identities.len()) {
+        return Err(AppSW::Deny);
+    }
+
+    let resp = compute_refresh_package(&secret, min_signers)?;
+
+    send_apdu_chunks(comm, resp.as_slice())
+}
+
+#[inline(never)]
+fn compute_refresh_package(secret: &Secret, min_signers: u8) -> Result<Vec<u8>, AppSW> {
+    zlog_stack("start compute_refresh_package\0");
+
+    let identities = DkgKeys.load_identities()?;
+
+    let mut entropy: Vec<[u8; 64]> = Vec::with_capacity(min_signers as usize - 1);
+    for _i in 1..min_signers {
+        let mut bytes = [0u8; 64];
+        rand_bytes(&mut bytes);
+        entropy.push(bytes);
+    }
+    let polynomial = Polynomial::new(Fr::zero(), &entropy);
+    drop(entropy);
+
+    let mut resp: Vec<u8> = Vec::new();
+    resp.extend_from_slice(secret.to_identity().serialize().as_ref());
+    resp.push(min_signers - 1);
+    for commitment in polynomial.commitments().iter().skip(1) {
+        resp.extend_from_slice(&point_to_bytes(commitment));
+    }
+
+    let mut rng = LedgerRng{};
+    resp.push(identities.len() as u8);
+    for identity in identities.iter() {
+        let x = identifier_to_scalar(&identity.to_frost_identifier()).map_err(|_| AppSW::DkgRefreshFail)?;
+
+        let mut share = polynomial.evaluate(&x).to_bytes();
+        let encrypted = multienc::encrypt(&share, [identity], &mut rng);
+        share.fill(0);
+
+        resp.extend_from_slice(&(encrypted.len() as u16).to_be_bytes());
+        resp.extend_from_slice(&encrypted);
+    }
+
+    Ok(resp)
+}
+
+fn send_apdu_chunks(comm: &mut Comm, data: &[u8]) -> Result<(), AppSW> {
+    let total_chunks = (data.len() + MAX_APDU_SIZE - 1) / MAX_APDU_SIZE;
+
+    for (i, chunk) in data.chunks(MAX_APDU_SIZE).enumerate() {
+        comm.append(chunk);
+
+        if i < total_chunks - 1 {
+            comm.reply_ok();
+            match comm.next_event() {
+                Event::Command(Instruction::DkgRefreshRound1 { .. }) => {}
+                _ => {},
+            }
+        }
+    }
+
+    Ok(())
+}
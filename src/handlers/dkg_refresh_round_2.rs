@@ -0,0 +1,232 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::{AppSW, Instruction};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use ironfish_frost::frost::keys::{KeyPackage, SigningShare, VerifyingShare};
+use ironfish_frost::frost::keys::PublicKeyPackage as FrostPublicKeyPackage;
+use ironfish_frost::multienc;
+use ironfish_frost::participant::Secret;
+use jubjub::{ExtendedPoint, Fr};
+use ledger_device_sdk::io::{Comm, Event};
+use crate::accumulator::accumulate_data;
+use crate::context::TxContext;
+use crate::handlers::dkg_get_identity::{compute_dkg_secret, read_identity_path, IdentityPath};
+use crate::handlers::dkg_reshare_round_2::{account_digest, confirm_account, parse_dealer_package, DealerPackage};
+use crate::ironfish::shares::{
+    evaluate_commitments, identifier_to_scalar, point_from_bytes, point_to_bytes,
+    scalar_from_bytes, verify_share,
+};
+use crate::nvm::buffer::Buffer;
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::nvm::dkg_secrets::{DkgSecrets, StagedAccount};
+use crate::utils::zlog_stack;
+
+const MAX_APDU_SIZE: usize = 253;
+
+pub struct Tx {
+    identity_path: IdentityPath,
+    dealer_packages: Vec<Vec<u8>>,
+}
+
+/// Second round of a share refresh. The shares of zero dealt to us by every
+/// participant are checked against their commitments and added to our share. The
+/// verifying shares of all the participants are updated the same way, and the
+/// verifying key must stay the same. The refreshed account is staged until every
+/// participant confirmed it in the third round.
+///
+/// The response is a digest of the refreshed public account data, signed with our
+/// identity, to be sent to the other participants.
+#[inline(never)]
+pub fn handler_dkg_refresh_round_2(
+    comm: &mut Comm,
+    chunk: u8,
+    flags: u8,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_refresh_round_2\0");
+
+    accumulate_data(comm, chunk, ctx)?;
+    if !ctx.done {
+        return Ok(());
+    }
+
+    let tx = parse_tx(&ctx.buffer, flags)?;
+    let secret = compute_dkg_secret(&tx.identity_path);
+
+    let account = compute_refreshed_account(&secret, &tx)?;
+    drop(tx);
+
+    let digest = account_digest(&account.identities, account.min_signers, &account.public_key_package)?;
+    DkgSecrets.save_staged_account(&account)?;
+    drop(account);
+
+    send_apdu_chunks(comm, &confirm_account(&secret, &digest))
+}
+
+#[inline(never)]
+fn parse_tx(buffer: &Buffer, flags: u8) -> Result<Tx, AppSW>{
+    zlog_stack("start parse_tx refresh round2\0");
+
+    let (identity_path, mut tx_pos) = read_identity_path(buffer, 0, flags)?;
+
+    let elements = buffer.get_element(tx_pos)?;
+    tx_pos += 1;
+
+    let mut dealer_packages = Vec::with_capacity(elements as usize);
+    for _i in 0..elements {
+        let len = buffer.get_u16(tx_pos)?;
+        tx_pos += 2;
+
+        let package = buffer.get_slice(tx_pos, tx_pos+len)?;
+        tx_pos += len;
+
+        dealer_packages.push(package.to_vec());
+    }
+
+    if tx_pos != buffer.pos {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    Ok(Tx{identity_path, dealer_packages})
+}
+
+#[inline(never)]
+fn compute_refreshed_account(secret: &Secret, tx: &Tx) -> Result<StagedAccount, AppSW> {
+    zlog_stack("start compute_refreshed_account\0");
+
+    let identities = DkgKeys.load_identities()?;
+    let min_signers = DkgKeys.load_min_signers()? as u8;
+    let key_package = DkgKeys.load_key_package()?;
+    let public_key_package = DkgKeys.load_frost_public_key_package()?;
+    let group_secret_key = DkgKeys.load_group_secret_key()?;
+
+    let own_identity = secret.to_identity();
+    let own_index = identities.iter().position(|i| *i == own_identity).ok_or(AppSW::DkgRefreshFail)?;
+    let own_identifier = own_identity.to_frost_identifier();
+    if own_identifier != *key_package.identifier() {
+        return Err(AppSW::InvalidKeyPackage);
+    }
+    let own_x = identifier_to_scalar(&own_identifier).map_err(|_| AppSW::DkgRefreshFail)?;
+
+    // Every participant must deal exactly once
+    if tx.dealer_packages.len() != identities.len() {
+        return Err(AppSW::DkgRefreshFail);
+    }
+    let mut dealers = Vec::with_capacity(tx.dealer_packages.len());
+    for data in tx.dealer_packages.iter() {
+        let dealer = parse_dealer_package(data, min_signers - 1, identities.len())?;
+        if !identities.contains(&dealer.identity) || dealers.iter().any(|d: &DealerPackage| d.identity == dealer.identity) {
+            return Err(AppSW::DkgRefreshFail);
+        }
+        dealers.push(dealer);
+    }
+
+    let mut delta = Fr::zero();
+    let mut group_commitments: Vec<ExtendedPoint> = Vec::new();
+    group_commitments.resize(min_signers as usize, ExtendedPoint::identity());
+
+    for dealer in dealers.iter() {
+        // The constant term is zero, its commitment is implied
+        let mut commitments = Vec::with_capacity(min_signers as usize);
+        commitments.push(ExtendedPoint::identity());
+        commitments.extend_from_slice(&dealer.commitments);
+
+        let mut plaintext = multienc::decrypt(secret, &dealer.encrypted_shares[own_index])
+            .map_err(|_| AppSW::DkgRefreshFail)?;
+        let share = scalar_from_bytes(&plaintext).map_err(|_| AppSW::DkgRefreshFail);
+        plaintext.fill(0);
+        let share = share?;
+
+        if !verify_share(&share, &commitments, &own_x) {
+            return Err(AppSW::DkgRefreshFail);
+        }
+
+        delta += share;
+        for (sum, commitment) in group_commitments.iter_mut().zip(commitments.iter()) {
+            *sum += commitment;
+        }
+    }
+    drop(dealers);
+
+    // Shares of zero add up to zero, so the account key stays the same
+    let verifying_key = *public_key_package.verifying_key();
+    if group_commitments[0] != ExtendedPoint::identity() {
+        return Err(AppSW::DkgRefreshFail);
+    }
+
+    let mut verifying_shares = BTreeMap::new();
+    for identity in identities.iter() {
+        let identifier = identity.to_frost_identifier();
+        let old_share = public_key_package.verifying_shares().get(&identifier).ok_or(AppSW::InvalidPublicPackage)?;
+        let old_share = point_from_bytes(
+            old_share.serialize().map_err(|_| AppSW::InvalidPublicPackage)?.as_ref()
+        ).map_err(|_| AppSW::InvalidPublicPackage)?;
+
+        let x = identifier_to_scalar(&identifier).map_err(|_| AppSW::DkgRefreshFail)?;
+        let point = old_share + evaluate_commitments(&group_commitments, &x);
+        let verifying_share = VerifyingShare::deserialize(&point_to_bytes(&point))
+            .map_err(|_| AppSW::DkgRefreshFail)?;
+        verifying_shares.insert(identifier, verifying_share);
+    }
+
+    let own_verifying_share = *verifying_shares.get(&own_identifier).ok_or(AppSW::DkgRefreshFail)?;
+
+    let old_signing_share = scalar_from_bytes(key_package.signing_share().serialize().as_ref())
+        .map_err(|_| AppSW::InvalidKeyPackage)?;
+    drop(key_package);
+
+    let mut signing_share_bytes = (old_signing_share + delta).to_bytes();
+    let signing_share = SigningShare::deserialize(&signing_share_bytes).map_err(|_| AppSW::DkgRefreshFail);
+    signing_share_bytes.fill(0);
+
+    let key_package = KeyPackage::new(
+        own_identifier,
+        signing_share?,
+        own_verifying_share,
+        verifying_key,
+        min_signers as u16,
+    );
+    let public_key_package = FrostPublicKeyPackage::new(verifying_shares, verifying_key);
+
+    Ok(StagedAccount {
+        identities,
+        min_signers,
+        key_package,
+        public_key_package,
+        group_secret_key,
+    })
+}
+
+fn send_apdu_chunks(comm: &mut Comm, data: &[u8]) -> Result<(), AppSW> {
+    let total_chunks = (data.len() + MAX_APDU_SIZE - 1) / MAX_APDU_SIZE;
+
+    for (i, chunk) in data.chunks(MAX_APDU_SIZE).enumerate() {
+        comm.append(chunk);
+
+        if i < total_chunks - 1 {
+            comm.reply_ok();
+            match comm.next_event() {
+                Event::Command(Instruction::DkgRefreshRound2 { chunk: 0, .. }) => {}
+                _ => {},
+            }
+        }
+    }
+
+    Ok(())
+}
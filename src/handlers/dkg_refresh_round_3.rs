@@ -0,0 +1,77 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::{AppSW};
+use ledger_device_sdk::io::{Comm};
+use crate::accumulator::accumulate_data;
+use crate::app_ui::dkg::{ui_review_account, REFRESHED_ACCOUNT};
+use crate::context::TxContext;
+use crate::handlers::dkg_reshare_round_2::{account_address, account_digest};
+use crate::handlers::dkg_reshare_round_3::check_digests;
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::nvm::dkg_secrets::DkgSecrets;
+use crate::utils::{zlog_stack};
+
+/// Last round of a share refresh. The staged account replaces the stored one once
+/// every participant sent the same digest as ours, signed with their identity, and
+/// the user confirmed it. Until then the current shares are kept.
+#[inline(never)]
+pub fn handler_dkg_refresh_round_3(
+    comm: &mut Comm,
+    chunk: u8,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_refresh_round_3\0");
+
+    accumulate_data(comm, chunk, ctx)?;
+    if !ctx.done {
+        return Ok(());
+    }
+
+    let account = DkgSecrets.load_staged_account()?;
+
+    // Only a refresh of the stored account, a staged reshare goes through its own round 3
+    let public_key_package = DkgKeys.load_frost_public_key_package()?;
+    if account.identities != DkgKeys.load_identities()?
+        || account.min_signers as usize != DkgKeys.load_min_signers()?
+        || account.public_key_package.verifying_key() != public_key_package.verifying_key()
+    {
+        return Err(AppSW::DkgRefreshFail);
+    }
+    drop(public_key_package);
+
+    let digest = account_digest(&account.identities, account.min_signers, &account.public_key_package)?;
+    check_digests(&ctx.buffer, &digest, &account.identities)?;
+
+    let address = account_address(&account)?;
+    if !ui_review_account(&REFRESHED_ACCOUNT, &address, account.min_signers, account.identities.len()) {
+        return Err(AppSW::Deny);
+    }
+
+    DkgKeys.save_account(
+        &account.identities,
+        account.min_signers,
+        &account.key_package,
+        &account.public_key_package,
+        &account.group_secret_key,
+    )?;
+    drop(account);
+
+    DkgSecrets.clear_staged_account();
+
+    Ok(())
+}
@@ -0,0 +1,232 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::{AppSW, Instruction};
+use alloc::vec::Vec;
+use ironfish_frost::multienc;
+use ironfish_frost::participant::{Identity, Secret, IDENTITY_LEN};
+use jubjub::Fr;
+use ledger_device_sdk::io::{Comm, Event};
+use ledger_device_sdk::random::{rand_bytes, LedgerRng};
+use crate::accumulator::accumulate_data;
+use crate::app_ui::dkg::{ui_confirm_share_deletion, ui_review_reshare};
+use crate::context::TxContext;
+use crate::handlers::dkg_get_identity::{compute_dkg_secret, read_identity_path, IdentityPath};
+use crate::ironfish::shares::{identifier_to_scalar, lagrange_coefficient, point_to_bytes, scalar_from_bytes, Polynomial};
+use crate::nvm::buffer::Buffer;
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::utils::zlog_stack;
+
+const MAX_APDU_SIZE: usize = 253;
+
+pub struct Tx {
+    identity_path: IdentityPath,
+    identities: Vec<Identity>,
+    min_signers: u8,
+    dealers: Vec<Identity>,
+}
+
+/// First round of a reshare, run by the current participants taking part in it (the
+/// dealers). Each dealer splits its own share, weighted by its Lagrange coefficient
+/// in the set of dealers, between the new participants. The shares of the new
+/// participants then add up to a sharing of the same account key.
+///
+/// The response is the dealer package: `identity | min_signers | commitments |
+/// n | encrypted (share | gsk) for each new participant`, in the order of the new
+/// identities.
+#[inline(never)]
+pub fn handler_dkg_reshare_round_1(
+    comm: &mut Comm,
+    chunk: u8,
+    flags: u8,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_reshare_round_1\0");
+
+    accumulate_data(comm, chunk, ctx)?;
+    if !ctx.done {
+        return Ok(());
+    }
+
+    let tx = parse_tx(&ctx.buffer, flags)?;
+    let secret = compute_dkg_secret(&tx.identity_path);
+
+    check_participants(&secret, &tx)?;
+
+    if !ui_review_reshare(&tx.identities, tx.min_signers) {
+        return Err(AppSW::Deny);
+    }
+
+    let resp = compute_dealer_package(&secret, &tx)?;
+
+    // Our share is not part of the new account, together with the shares of other
+    // leaving participants it could still sign for the account
+    if !tx.identities.contains(&secret.to_identity()) {
+        let address = DkgKeys.load_public_address()?;
+        if ui_confirm_share_deletion(&address) {
+            DkgKeys.clear();
+        }
+    }
+
+    send_apdu_chunks(comm, resp.as_slice())
+}
+
+/// Reads `n | identity * n`, returning the identities and the position after them
+pub fn parse_identities(buffer: &Buffer, mut tx_pos: usize) -> Result<(Vec<Identity>, usize), AppSW> {
+    let elements = buffer.get_element(tx_pos)?;
+    tx_pos +=1;
+
+    let mut identities:Vec<Identity> = Vec::with_capacity(elements as usize);
+    for _i in 0..elements {
+        let data = buffer.get_slice(tx_pos,tx_pos+IDENTITY_LEN)?;
+        let identity
+            = Identity::deserialize_from(data).map_err(|_| AppSW::InvalidIdentity)?;
+        tx_pos += IDENTITY_LEN;
+
+        identities.push(identity);
+    }
+
+    Ok((identities, tx_pos))
+}
+
+fn parse_tx(buffer: &Buffer, flags: u8) -> Result<Tx, AppSW>{
+    let (identity_path, tx_pos) = read_identity_path(buffer, 0, flags)?;
+
+    let (identities, tx_pos) = parse_identities(buffer, tx_pos)?;
+
+    let min_signers = buffer.get_element(tx_pos)?;
+    let tx_pos = tx_pos + 1;
+
+    let (dealers, tx_pos) = parse_identities(buffer, tx_pos)?;
+
+    if tx_pos != buffer.pos {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    Ok(Tx{identity_path, identities, min_signers, dealers})
+}
+
+pub fn has_duplicates(identities: &[Identity]) -> bool {
+    identities.iter().enumerate().any(|(i, identity)| identities[..i].contains(identity))
+}
+
+/// Dealers must be enough participants of the stored account, ourselves included,
+/// and the new signer set must be usable.
+#[inline(never)]
+fn check_participants(secret: &Secret, tx: &Tx) -> Result<(), AppSW> {
+    zlog_stack("start check_participants\0");
+
+    let identities = DkgKeys.load_identities()?;
+    let min_signers = DkgKeys.load_min_signers()?;
+
+    if has_duplicates(&tx.dealers)
+        || tx.dealers.len() < min_signers
+        || !tx.dealers.iter().all(|d| identities.contains(d))
+        || !tx.dealers.contains(&secret.to_identity())
+    {
+        return Err(AppSW::DkgReshareFail);
+    }
+
+    if has_duplicates(&tx.identities)
+        || tx.min_signers < 2
+        || tx.min_signers as usize > tx.identities.len()
+    {
+        return Err(AppSW::DkgReshareFail);
+    }
+
+    Ok(())
+}
+
+#[inline(never)]
+fn compute_dealer_package(secret: &Secret, tx: &Tx) -> Result<Vec<u8>, AppSW> {
+    zlog_stack("start compute_dealer_package\0");
+
+    let key_package = DkgKeys.load_key_package()?;
+    let group_secret_key = DkgKeys.load_group_secret_key()?;
+
+    let own_identity = secret.to_identity();
+    if own_identity.to_frost_identifier() != *key_package.identifier() {
+        return Err(AppSW::InvalidKeyPackage);
+    }
+
+    let mut dealers_x: Vec<Fr> = Vec::with_capacity(tx.dealers.len());
+    for dealer in tx.dealers.iter() {
+        dealers_x.push(identifier_to_scalar(&dealer.to_frost_identifier()).map_err(|_| AppSW::DkgReshareFail)?);
+    }
+    let own_x = identifier_to_scalar(key_package.identifier()).map_err(|_| AppSW::DkgReshareFail)?;
+    let lambda = lagrange_coefficient(&own_x, &dealers_x).map_err(|_| AppSW::DkgReshareFail)?;
+
+    let signing_share = scalar_from_bytes(key_package.signing_share().serialize().as_ref())
+        .map_err(|_| AppSW::InvalidKeyPackage)?;
+    drop(key_package);
+
+    let mut entropy: Vec<[u8; 64]> = Vec::with_capacity(tx.min_signers as usize - 1);
+    for _i in 1..tx.min_signers {
+        let mut bytes = [0u8; 64];
+        rand_bytes(&mut bytes);
+        entropy.push(bytes);
+    }
+    let polynomial = Polynomial::new(lambda * signing_share, &entropy);
+    drop(entropy);
+
+    let mut resp: Vec<u8> = Vec::new();
+    resp.extend_from_slice(own_identity.serialize().as_ref());
+    resp.push(tx.min_signers);
+    for commitment in polynomial.commitments().iter() {
+        resp.extend_from_slice(&point_to_bytes(commitment));
+    }
+
+    let mut rng = LedgerRng{};
+    resp.push(tx.identities.len() as u8);
+    for identity in tx.identities.iter() {
+        let x = identifier_to_scalar(&identity.to_frost_identifier()).map_err(|_| AppSW::DkgReshareFail)?;
+
+        let mut plaintext = [0u8; 64];
+        plaintext[..32].copy_from_slice(&polynomial.evaluate(&x).to_bytes());
+        plaintext[32..].copy_from_slice(&group_secret_key);
+
+        let encrypted = multienc::encrypt(&plaintext, [identity], &mut rng);
+        plaintext.fill(0);
+
+        resp.extend_from_slice(&(encrypted.len() as u16).to_be_bytes());
+        resp.extend_from_slice(&encrypted);
+    }
+
+    Ok(resp)
+}
+
+/// Sends the response of a reshare round, in as many APDUs as needed
+pub fn send_apdu_chunks(comm: &mut Comm, data: &[u8]) -> Result<(), AppSW> {
+    let total_chunks = (data.len() + MAX_APDU_SIZE - 1) / MAX_APDU_SIZE;
+
+    for (i, chunk) in data.chunks(MAX_APDU_SIZE).enumerate() {
+        comm.append(chunk);
+
+        if i < total_chunks - 1 {
+            comm.reply_ok();
+            match comm.next_event() {
+                Event::Command(
+                    Instruction::DkgReshareRound1 { chunk: 0, .. }
+                    | Instruction::DkgReshareRound2 { chunk: 0, .. }
+                ) => {}
+                _ => {},
+            }
+        }
+    }
+
+    Ok(())
+}
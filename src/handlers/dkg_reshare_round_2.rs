@@ -0,0 +1,392 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::AppSW;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use blake2b_simd::Params as Blake2b;
+use ed25519_dalek::{Signature, Signer, SIGNATURE_LENGTH};
+use ironfish_frost::dkg::group_key::{GroupSecretKey, GROUP_SECRET_KEY_LEN};
+use ironfish_frost::frost::keys::{KeyPackage, SigningShare, VerifyingShare};
+use ironfish_frost::frost::keys::PublicKeyPackage as FrostPublicKeyPackage;
+use ironfish_frost::frost::VerifyingKey;
+use ironfish_frost::multienc;
+use ironfish_frost::participant::{Identity, Secret, IDENTITY_LEN};
+use jubjub::{ExtendedPoint, Fr};
+use ledger_device_sdk::io::Comm;
+use crate::accumulator::accumulate_data;
+use crate::app_ui::dkg::{ui_review_account, JOINED_ACCOUNT};
+use crate::context::TxContext;
+use crate::handlers::dkg_get_identity::{compute_dkg_secret, read_identity_path, IdentityPath};
+use crate::handlers::dkg_reshare_round_1::{has_duplicates, parse_identities, send_apdu_chunks};
+use crate::ironfish::constants::ACCOUNT_CONFIRMATION_DOMAIN;
+use crate::ironfish::multisig::derive_account_keys;
+use crate::ironfish::public_address::PUBLIC_ADDRESS_SIZE;
+use crate::ironfish::shares::{
+    evaluate_commitments, identifier_to_scalar, lagrange_coefficient, point_from_bytes,
+    point_to_bytes, scalar_from_bytes, verify_share, POINT_LEN, SCALAR_LEN,
+};
+use crate::nvm::buffer::Buffer;
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::nvm::dkg_secrets::{DkgSecrets, StagedAccount};
+use crate::utils::zlog_stack;
+
+const ACCOUNT_DIGEST_PERSONALIZATION: &[u8; 16] = b"IFReshareAccount";
+pub const ACCOUNT_DIGEST_LEN: usize = 32;
+pub const ACCOUNT_CONFIRMATION_LEN: usize = ACCOUNT_DIGEST_LEN + SIGNATURE_LENGTH;
+
+pub struct Tx {
+    identity_path: IdentityPath,
+    identities: Vec<Identity>,
+    min_signers: u8,
+    public_key_package: FrostPublicKeyPackage,
+    dealer_packages: Vec<Vec<u8>>,
+}
+
+/// Package dealt by one of the current participants in the first round
+pub struct DealerPackage {
+    pub identity: Identity,
+    pub commitments: Vec<ExtendedPoint>,
+    pub encrypted_shares: Vec<Vec<u8>>,
+}
+
+/// Second round of a reshare, run by every new participant. The shares dealt to us
+/// are checked against the dealers commitments, and the commitments against the
+/// public key package of the account being reshared, so the resulting account
+/// always has the same verifying key. The account is staged until every new
+/// participant confirmed, in the third round, that they got the same one.
+///
+/// The response is a digest of the new public account data, signed with our identity,
+/// to be sent to the other participants.
+#[inline(never)]
+pub fn handler_dkg_reshare_round_2(
+    comm: &mut Comm,
+    chunk: u8,
+    flags: u8,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_reshare_round_2\0");
+
+    accumulate_data(comm, chunk, ctx)?;
+    if !ctx.done {
+        return Ok(());
+    }
+
+    let tx = parse_tx(&ctx.buffer, flags)?;
+    let secret = compute_dkg_secret(&tx.identity_path);
+
+    let account = compute_reshared_account(&secret, &tx)?;
+    drop(tx);
+
+    // Without a stored account, nothing tells us the account being reshared is the
+    // one the user is joining, only the user can check its address
+    if !DkgKeys.has_account() {
+        let address = account_address(&account)?;
+        if !ui_review_account(&JOINED_ACCOUNT, &address, account.min_signers, account.identities.len()) {
+            return Err(AppSW::Deny);
+        }
+    }
+
+    let digest = account_digest(&account.identities, account.min_signers, &account.public_key_package)?;
+    DkgSecrets.save_staged_account(&account)?;
+    drop(account);
+
+    send_apdu_chunks(comm, &confirm_account(&secret, &digest))
+}
+
+#[inline(never)]
+fn parse_tx(buffer: &Buffer, flags: u8) -> Result<Tx, AppSW>{
+    zlog_stack("start parse_tx reshare round2\0");
+
+    let (identity_path, tx_pos) = read_identity_path(buffer, 0, flags)?;
+
+    let (identities, mut tx_pos) = parse_identities(buffer, tx_pos)?;
+
+    let min_signers = buffer.get_element(tx_pos)?;
+    tx_pos += 1;
+
+    let len = buffer.get_u16(tx_pos)?;
+    tx_pos += 2;
+    let data = buffer.get_slice(tx_pos, tx_pos+len)?;
+    let public_key_package
+        = FrostPublicKeyPackage::deserialize(data).map_err(|_| AppSW::InvalidPublicPackage)?;
+    tx_pos += len;
+
+    let elements = buffer.get_element(tx_pos)?;
+    tx_pos += 1;
+
+    let mut dealer_packages = Vec::with_capacity(elements as usize);
+    for _i in 0..elements {
+        let len = buffer.get_u16(tx_pos)?;
+        tx_pos += 2;
+
+        let package = buffer.get_slice(tx_pos, tx_pos+len)?;
+        tx_pos += len;
+
+        dealer_packages.push(package.to_vec());
+    }
+
+    if tx_pos != buffer.pos {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    Ok(Tx{identity_path, identities, min_signers, public_key_package, dealer_packages})
+}
+
+/// Layout: `identity | number of commitments | commitments | n | encrypted shares`,
+/// each encrypted share prefixed by its length
+pub fn parse_dealer_package(data: &[u8], commitments_len: u8, participants: usize) -> Result<DealerPackage, AppSW> {
+    let mut pos = 0;
+
+    let identity = data.get(pos..pos + IDENTITY_LEN).ok_or(AppSW::InvalidPayload)?;
+    let identity = Identity::deserialize_from(identity).map_err(|_| AppSW::InvalidIdentity)?;
+    pos += IDENTITY_LEN;
+
+    if *data.get(pos).ok_or(AppSW::InvalidPayload)? != commitments_len {
+        return Err(AppSW::DkgReshareFail);
+    }
+    pos += 1;
+
+    let mut commitments = Vec::with_capacity(commitments_len as usize);
+    for _i in 0..commitments_len {
+        let commitment = data.get(pos..pos + POINT_LEN).ok_or(AppSW::InvalidPayload)?;
+        commitments.push(point_from_bytes(commitment).map_err(|_| AppSW::DkgReshareFail)?);
+        pos += POINT_LEN;
+    }
+
+    if *data.get(pos).ok_or(AppSW::InvalidPayload)? as usize != participants {
+        return Err(AppSW::DkgReshareFail);
+    }
+    pos += 1;
+
+    let mut encrypted_shares = Vec::with_capacity(participants);
+    for _i in 0..participants {
+        let len = data.get(pos..pos + 2).ok_or(AppSW::InvalidPayload)?;
+        let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+        pos += 2;
+
+        let encrypted = data.get(pos..pos + len).ok_or(AppSW::InvalidPayload)?;
+        encrypted_shares.push(encrypted.to_vec());
+        pos += len;
+    }
+
+    if pos != data.len() {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    Ok(DealerPackage { identity, commitments, encrypted_shares })
+}
+
+#[inline(never)]
+fn compute_reshared_account(secret: &Secret, tx: &Tx) -> Result<StagedAccount, AppSW> {
+    zlog_stack("start compute_reshared_account\0");
+
+    let participants = tx.identities.len();
+    if tx.min_signers < 2 || tx.min_signers as usize > participants {
+        return Err(AppSW::DkgReshareFail);
+    }
+
+    let own_identity = secret.to_identity();
+    let own_index = tx.identities.iter().position(|i| *i == own_identity).ok_or(AppSW::DkgReshareFail)?;
+    if has_duplicates(&tx.identities) {
+        return Err(AppSW::DkgReshareFail);
+    }
+
+    // The account being reshared must be the one we already hold, if any
+    let old_verifying_key = tx.public_key_package.verifying_key();
+    if let Ok(stored) = DkgKeys.load_frost_public_key_package() {
+        if stored.verifying_key() != old_verifying_key {
+            return Err(AppSW::DkgReshareFail);
+        }
+    }
+    let old_verifying_key = point_from_bytes(
+        old_verifying_key.serialize().map_err(|_| AppSW::InvalidPublicPackage)?.as_ref()
+    ).map_err(|_| AppSW::InvalidPublicPackage)?;
+
+    let mut dealers = Vec::with_capacity(tx.dealer_packages.len());
+    for data in tx.dealer_packages.iter() {
+        dealers.push(parse_dealer_package(data, tx.min_signers, participants)?);
+    }
+
+    let mut dealers_x: Vec<Fr> = Vec::with_capacity(dealers.len());
+    for (i, dealer) in dealers.iter().enumerate() {
+        if dealers[..i].iter().any(|d| d.identity == dealer.identity) {
+            return Err(AppSW::DkgReshareFail);
+        }
+        dealers_x.push(identifier_to_scalar(&dealer.identity.to_frost_identifier()).map_err(|_| AppSW::DkgReshareFail)?);
+    }
+
+    let own_x = identifier_to_scalar(&own_identity.to_frost_identifier()).map_err(|_| AppSW::DkgReshareFail)?;
+
+    let mut signing_share = Fr::zero();
+    let mut group_commitments: Vec<ExtendedPoint> = Vec::new();
+    group_commitments.resize(tx.min_signers as usize, ExtendedPoint::identity());
+    let mut group_secret_key: Option<GroupSecretKey> = None;
+
+    for (dealer, x) in dealers.iter().zip(dealers_x.iter()) {
+        // The dealer must have shared its own share of the account key, weighted by
+        // its Lagrange coefficient, or the verifying key would change
+        let verifying_share = tx.public_key_package.verifying_shares()
+            .get(&dealer.identity.to_frost_identifier())
+            .ok_or(AppSW::DkgReshareFail)?;
+        let verifying_share = point_from_bytes(
+            verifying_share.serialize().map_err(|_| AppSW::InvalidPublicPackage)?.as_ref()
+        ).map_err(|_| AppSW::InvalidPublicPackage)?;
+        let lambda = lagrange_coefficient(x, &dealers_x).map_err(|_| AppSW::DkgReshareFail)?;
+        if dealer.commitments[0] != verifying_share * lambda {
+            return Err(AppSW::DkgReshareFail);
+        }
+
+        let mut plaintext = multienc::decrypt(secret, &dealer.encrypted_shares[own_index])
+            .map_err(|_| AppSW::DkgReshareFail)?;
+        if plaintext.len() != SCALAR_LEN + GROUP_SECRET_KEY_LEN {
+            return Err(AppSW::DkgReshareFail);
+        }
+
+        let share = scalar_from_bytes(&plaintext[..SCALAR_LEN]).map_err(|_| AppSW::DkgReshareFail)?;
+        if !verify_share(&share, &dealer.commitments, &own_x) {
+            return Err(AppSW::DkgReshareFail);
+        }
+
+        let gsk = <GroupSecretKey>::try_from(&plaintext[SCALAR_LEN..]).map_err(|_| AppSW::InvalidGroupSecretKey)?;
+        plaintext.fill(0);
+        match group_secret_key {
+            None => group_secret_key = Some(gsk),
+            Some(ref expected) if *expected == gsk => {},
+            Some(_) => return Err(AppSW::DkgReshareFail),
+        }
+
+        signing_share += share;
+        for (sum, commitment) in group_commitments.iter_mut().zip(dealer.commitments.iter()) {
+            *sum += commitment;
+        }
+    }
+
+    let group_secret_key = group_secret_key.ok_or(AppSW::DkgReshareFail)?;
+
+    // Fewer dealers than the old threshold can't rebuild the account key
+    if group_commitments[0] != old_verifying_key {
+        return Err(AppSW::DkgReshareFail);
+    }
+
+    let verifying_key = VerifyingKey::deserialize(&point_to_bytes(&group_commitments[0]))
+        .map_err(|_| AppSW::DkgReshareFail)?;
+
+    let mut verifying_shares = BTreeMap::new();
+    for identity in tx.identities.iter() {
+        let identifier = identity.to_frost_identifier();
+        let x = identifier_to_scalar(&identifier).map_err(|_| AppSW::DkgReshareFail)?;
+        let point = evaluate_commitments(&group_commitments, &x);
+        let verifying_share = VerifyingShare::deserialize(&point_to_bytes(&point))
+            .map_err(|_| AppSW::DkgReshareFail)?;
+        verifying_shares.insert(identifier, verifying_share);
+    }
+
+    let own_identifier = own_identity.to_frost_identifier();
+    let own_verifying_share = *verifying_shares.get(&own_identifier).ok_or(AppSW::DkgReshareFail)?;
+
+    let mut signing_share_bytes = signing_share.to_bytes();
+    let signing_share = SigningShare::deserialize(&signing_share_bytes).map_err(|_| AppSW::DkgReshareFail);
+    signing_share_bytes.fill(0);
+
+    let key_package = KeyPackage::new(
+        own_identifier,
+        signing_share?,
+        own_verifying_share,
+        verifying_key,
+        tx.min_signers as u16,
+    );
+    let public_key_package = FrostPublicKeyPackage::new(verifying_shares, verifying_key);
+
+    Ok(StagedAccount {
+        identities: tx.identities.clone(),
+        min_signers: tx.min_signers,
+        key_package,
+        public_key_package,
+        group_secret_key,
+    })
+}
+
+/// Digest of the public part of the new account, which must be the same for every
+/// participant of the reshare
+#[inline(never)]
+pub fn account_digest(
+    identities: &[Identity],
+    min_signers: u8,
+    public_key_package: &FrostPublicKeyPackage,
+) -> Result<[u8; ACCOUNT_DIGEST_LEN], AppSW> {
+    let public_key_package = public_key_package.serialize().map_err(|_| AppSW::InvalidPublicPackage)?;
+
+    let mut hasher = Blake2b::new()
+        .hash_length(ACCOUNT_DIGEST_LEN)
+        .personal(ACCOUNT_DIGEST_PERSONALIZATION)
+        .to_state();
+
+    hasher.update(&[min_signers, identities.len() as u8]);
+    for identity in identities.iter() {
+        hasher.update(identity.serialize().as_ref());
+    }
+    hasher.update(&public_key_package);
+
+    let mut digest = [0u8; ACCOUNT_DIGEST_LEN];
+    digest.copy_from_slice(hasher.finalize().as_bytes());
+    Ok(digest)
+}
+
+/// Message signed by a participant to confirm an account digest
+fn confirmation_message(digest: &[u8; ACCOUNT_DIGEST_LEN]) -> Vec<u8> {
+    let mut message: Vec<u8> = Vec::with_capacity(ACCOUNT_CONFIRMATION_DOMAIN.len() + ACCOUNT_DIGEST_LEN);
+    message.extend_from_slice(ACCOUNT_CONFIRMATION_DOMAIN);
+    message.extend_from_slice(digest);
+    message
+}
+
+/// Our confirmation of the new account, `digest | signature`, signed with our
+/// identity so the other participants know it comes from us
+pub fn confirm_account(secret: &Secret, digest: &[u8; ACCOUNT_DIGEST_LEN]) -> Vec<u8> {
+    let signature = secret.signing_key().sign(&confirmation_message(digest));
+
+    let mut confirmation: Vec<u8> = Vec::with_capacity(ACCOUNT_CONFIRMATION_LEN);
+    confirmation.extend_from_slice(digest);
+    confirmation.extend_from_slice(&signature.to_bytes());
+    confirmation
+}
+
+/// Checks a confirmation received from a participant is for our digest, and signed
+/// by their identity
+pub fn verify_confirmation(identity: &Identity, confirmation: &[u8], digest: &[u8; ACCOUNT_DIGEST_LEN]) -> bool {
+    if confirmation.len() != ACCOUNT_CONFIRMATION_LEN || confirmation[..ACCOUNT_DIGEST_LEN] != digest[..] {
+        return false;
+    }
+
+    let mut signature = [0u8; SIGNATURE_LENGTH];
+    signature.copy_from_slice(&confirmation[ACCOUNT_DIGEST_LEN..]);
+    let signature = Signature::from_bytes(&signature);
+
+    identity.verification_key().verify_strict(&confirmation_message(digest), &signature).is_ok()
+}
+
+/// Address of a staged account
+pub fn account_address(account: &StagedAccount) -> Result<[u8; PUBLIC_ADDRESS_SIZE], AppSW> {
+    let verifying_key_vec = account.public_key_package.verifying_key().serialize()
+        .map_err(|_| AppSW::InvalidPublicPackage)?;
+    let verifying_key = <&[u8; 32]>::try_from(verifying_key_vec.as_slice())
+        .map_err(|_| AppSW::InvalidPublicPackage)?;
+    let account_keys = derive_account_keys(verifying_key, &account.group_secret_key);
+
+    Ok(account_keys.public_address.public_address())
+}
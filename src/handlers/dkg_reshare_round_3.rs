@@ -0,0 +1,98 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::{AppSW};
+use ironfish_frost::participant::Identity;
+use ledger_device_sdk::io::{Comm};
+use crate::accumulator::accumulate_data;
+use crate::app_ui::dkg::{ui_review_account, RESHARED_ACCOUNT};
+use crate::context::TxContext;
+use crate::handlers::dkg_reshare_round_2::{
+    account_address, account_digest, verify_confirmation, ACCOUNT_CONFIRMATION_LEN, ACCOUNT_DIGEST_LEN,
+};
+use crate::nvm::buffer::Buffer;
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::nvm::dkg_secrets::DkgSecrets;
+use crate::utils::{zlog_stack};
+
+/// Last round of a reshare. Every new participant sends the digest it got at the end
+/// of the second round, signed with its identity, and the staged account replaces the
+/// stored one only if they all match ours and the user confirms it.
+#[inline(never)]
+pub fn handler_dkg_reshare_round_3(
+    comm: &mut Comm,
+    chunk: u8,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_reshare_round_3\0");
+
+    accumulate_data(comm, chunk, ctx)?;
+    if !ctx.done {
+        return Ok(());
+    }
+
+    let account = DkgSecrets.load_staged_account()?;
+    let digest = account_digest(&account.identities, account.min_signers, &account.public_key_package)?;
+
+    check_digests(&ctx.buffer, &digest, &account.identities)?;
+
+    let address = account_address(&account)?;
+
+    if !ui_review_account(&RESHARED_ACCOUNT, &address, account.min_signers, account.identities.len()) {
+        return Err(AppSW::Deny);
+    }
+
+    DkgKeys.save_account(
+        &account.identities,
+        account.min_signers,
+        &account.key_package,
+        &account.public_key_package,
+        &account.group_secret_key,
+    )?;
+    drop(account);
+
+    DkgSecrets.clear_staged_account();
+
+    Ok(())
+}
+
+/// Every new participant must have confirmed the same account. The payload is
+/// `n | (digest | signature) * n`, in the order of the account identities, each
+/// digest signed by the identity of the participant it comes from.
+pub fn check_digests(buffer: &Buffer, digest: &[u8; ACCOUNT_DIGEST_LEN], identities: &[Identity]) -> Result<(), AppSW> {
+    let elements = buffer.get_element(0)? as usize;
+    let mut tx_pos = 1;
+
+    if elements != identities.len() {
+        return Err(AppSW::DkgReshareFail);
+    }
+
+    for identity in identities.iter() {
+        let confirmation = buffer.get_slice(tx_pos, tx_pos + ACCOUNT_CONFIRMATION_LEN)?;
+        tx_pos += ACCOUNT_CONFIRMATION_LEN;
+
+        if !verify_confirmation(identity, confirmation, digest) {
+            return Err(AppSW::DkgReshareFail);
+        }
+    }
+
+    if tx_pos != buffer.pos {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    Ok(())
+}
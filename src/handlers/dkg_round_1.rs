@@ -23,16 +23,18 @@ use ironfish_frost::participant::{Identity, Secret};
 use ledger_device_sdk::io::{Comm, Event};
 use crate::accumulator::accumulate_data;
 use crate::nvm::buffer::{Buffer};
-use crate::handlers::dkg_get_identity::compute_dkg_secret;
+use crate::handlers::dkg_get_identity::{compute_dkg_secret, read_identity_path, IdentityPath};
 use crate::context::TxContext;
 use crate::nvm::dkg_keys::DkgKeys;
+use crate::nvm::dkg_secrets::{DkgSecrets, SECRETS_ON_DEVICE};
 use crate::utils::{zlog, zlog_stack};
+use crate::app_ui::dkg::ui_review_dkg_round_1;
 
 const MAX_APDU_SIZE: usize = 253;
 const IDENTITY_LEN: usize = 129;
 
 pub struct Tx {
-    identity_index: u8,
+    identity_path: IdentityPath,
     identities: Vec<Identity>,
     min_signers: u8,
 }
@@ -41,6 +43,7 @@ pub struct Tx {
 pub fn handler_dkg_round_1(
     comm: &mut Comm,
     chunk: u8,
+    flags: u8,
     ctx: &mut TxContext,
 ) -> Result<(), AppSW> {
     zlog_stack("start handler_dkg_round_1\0");
@@ -50,19 +53,28 @@ pub fn handler_dkg_round_1(
         return Ok(());
     }
 
-    let mut tx: Tx = parse_tx(&ctx.buffer)?;
-    let dkg_secret = compute_dkg_secret(tx.identity_index);
+    let mut tx: Tx = parse_tx(&ctx.buffer, flags)?;
 
-    compute_dkg_round_1(comm, &dkg_secret, &mut tx)?;
+    if !ui_review_dkg_round_1(&tx.identities, tx.min_signers) {
+        return Err(AppSW::Deny);
+    }
+
+    // Secrets of a previous ceremony run with the device keeping them must not be
+    // mixed with this one, the host holds them this time
+    let keep_secret = flags & SECRETS_ON_DEVICE != 0;
+    if !keep_secret {
+        DkgSecrets.clear_round_secrets();
+    }
+
+    let dkg_secret = compute_dkg_secret(&tx.identity_path);
+
+    compute_dkg_round_1(comm, &dkg_secret, &mut tx, keep_secret)?;
 
     DkgKeys.save_round_1_data(&tx.identities, tx.min_signers)
 }
 
-fn parse_tx(buffer: &Buffer) -> Result<Tx, AppSW>{
-    let mut tx_pos:usize = 0;
-
-    let identity_index = buffer.get_element(tx_pos)?;
-    tx_pos +=1;
+fn parse_tx(buffer: &Buffer, flags: u8) -> Result<Tx, AppSW>{
+    let (identity_path, mut tx_pos) = read_identity_path(buffer, 0, flags)?;
 
     let elements = buffer.get_element(tx_pos)?;
     tx_pos +=1;
@@ -84,10 +96,10 @@ fn parse_tx(buffer: &Buffer) -> Result<Tx, AppSW>{
         return Err(AppSW::InvalidPayload);
     }
 
-    Ok(Tx{identities, min_signers, identity_index})
+    Ok(Tx{identities, min_signers, identity_path})
 }
 
-fn compute_dkg_round_1(comm: &mut Comm, secret: &Secret, tx: &mut Tx) -> Result<(), AppSW> {
+fn compute_dkg_round_1(comm: &mut Comm, secret: &Secret, tx: &mut Tx, keep_secret: bool) -> Result<(), AppSW> {
     zlog("start compute_dkg_round_1\n\0");
 
     let mut rng = LedgerRng{};
@@ -99,6 +111,12 @@ fn compute_dkg_round_1(comm: &mut Comm, secret: &Secret, tx: &mut Tx) -> Result<
         &mut rng,
     ).unwrap();
 
+    // The secret package stays on the device, the host gets an empty one
+    if keep_secret {
+        DkgSecrets.save_round_1_secret(&round1_secret_package_vec)?;
+        round1_secret_package_vec.clear();
+    }
+
     let mut resp : Vec<u8> = Vec::new();
     let mut round1_public_package_vec = round1_public_package.serialize();
     let round1_public_package_len = round1_public_package_vec.len();
@@ -123,7 +141,7 @@ fn send_apdu_chunks(comm: &mut Comm, data: &[u8]) -> Result<(), AppSW> {
         if i < total_chunks - 1 {
             comm.reply_ok();
             match comm.next_event() {
-                Event::Command(Instruction::DkgRound1 { chunk: 0 }) => {}
+                Event::Command(Instruction::DkgRound1 { chunk: 0, .. }) => {}
                 _ => {},
             }
         }
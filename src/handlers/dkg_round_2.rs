@@ -27,9 +27,11 @@ use ledger_device_sdk::io::{Comm, Event};
 use serde_json_core::to_string;
 use crate::accumulator::accumulate_data;
 use crate::nvm::buffer::{Buffer};
-use crate::handlers::dkg_get_identity::compute_dkg_secret;
+use crate::handlers::dkg_get_identity::{compute_dkg_secret, read_identity_path};
 use crate::context::TxContext;
 use crate::utils::{zlog_stack};
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::nvm::dkg_secrets::{DkgSecrets, SECRETS_ON_DEVICE};
 
 const MAX_APDU_SIZE: usize = 253;
 
@@ -37,6 +39,7 @@ const MAX_APDU_SIZE: usize = 253;
 pub fn handler_dkg_round_2(
     comm: &mut Comm,
     chunk: u8,
+    flags: u8,
     ctx: &mut TxContext,
 ) -> Result<(), AppSW> {
     zlog_stack("start handler_dkg_round_2\0");
@@ -46,12 +49,26 @@ pub fn handler_dkg_round_2(
         return Ok(());
     }
 
-    let identity_index = ctx.buffer.get_element(0)?;
-    let (round_1_public_packages, current_pos) = parse_round_1_public_packages(&ctx.buffer, 1)?;
-    let (round_1_secret_package, current_pos) = parse_round_1_secret_package(&ctx.buffer, current_pos)?;
+    let (identity_path, current_pos) = read_identity_path(&ctx.buffer, 0, flags)?;
+    let (round_1_public_packages, current_pos) = parse_round_1_public_packages(&ctx.buffer, current_pos)?;
+    let keep_secret = flags & SECRETS_ON_DEVICE != 0;
+    let (round_1_secret_package, current_pos) = if keep_secret {
+        (DkgSecrets.load_round_1_secret()?, current_pos)
+    } else {
+        parse_round_1_secret_package(&ctx.buffer, current_pos)?
+    };
+
+    let secret = compute_dkg_secret(&identity_path);
+    check_round_1_public_packages(&secret, &round_1_public_packages)?;
 
     let (mut round2_secret_package_vec, round2_public_package)
-        = compute_dkg_round_2(identity_index, round_1_public_packages, round_1_secret_package)?;
+        = compute_dkg_round_2(&secret, round_1_public_packages, round_1_secret_package)?;
+
+    // The secret package stays on the device, the host gets an empty one
+    if keep_secret {
+        DkgSecrets.save_round_2_secret(&round2_secret_package_vec)?;
+        round2_secret_package_vec.clear();
+    }
 
     let response = generate_response(&mut round2_secret_package_vec, &round2_public_package);
     drop(round2_secret_package_vec);
@@ -94,16 +111,50 @@ fn parse_round_1_secret_package(buffer: &Buffer, mut tx_pos:usize) -> Result<(&[
     Ok((data, tx_pos))
 }
 
+/// The round 1 packages must come from the ceremony started on this device: one
+/// package for each participant saved in round 1, ours included, all of them built
+/// for the same participants and min signers.
+#[inline(never)]
+fn check_round_1_public_packages(secret: &Secret, round_1_public_packages: &[PublicPackage]) -> Result<(), AppSW> {
+    zlog_stack("start check_round_1_public_packages\0");
+
+    let identities = DkgKeys.load_identities()?;
+    let min_signers = DkgKeys.load_min_signers()?;
+
+    if round_1_public_packages.len() != identities.len() {
+        return Err(AppSW::InvalidRound1Packages);
+    }
+
+    for identity in identities.iter() {
+        let count = round_1_public_packages.iter().filter(|p| p.identity() == identity).count();
+        if count != 1 {
+            return Err(AppSW::InvalidRound1Packages);
+        }
+    }
+
+    let own_identity = secret.to_identity();
+    if !identities.contains(&own_identity) {
+        return Err(AppSW::InvalidRound1Packages);
+    }
+
+    // The checksum covers both the participants and min signers
+    let expected_checksum = dkg::round1::input_checksum(min_signers as u16, &identities);
+    if round_1_public_packages.iter().any(|p| p.checksum() != expected_checksum) {
+        return Err(AppSW::InvalidRound1Packages);
+    }
+
+    Ok(())
+}
+
 #[inline(never)]
-fn compute_dkg_round_2(identity_index: u8, round_1_public_packages:Vec<PublicPackage>, round_1_secret_package: &[u8])
+fn compute_dkg_round_2(secret: &Secret, round_1_public_packages:Vec<PublicPackage>, round_1_secret_package: &[u8])
     -> Result<(Vec<u8>, CombinedPublicPackage), AppSW> {
     zlog_stack("start compute_dkg_round_2\0");
 
     let mut rng = LedgerRng{};
-    let secret = compute_dkg_secret(identity_index);
 
     dkg::round2::round2(
-        &secret,
+        secret,
         round_1_secret_package,
         &round_1_public_packages,
         &mut rng,
@@ -136,7 +187,7 @@ fn send_apdu_chunks(comm: &mut Comm, data_vec: &Vec<u8>) -> Result<(), AppSW> {
         if i < total_chunks - 1 {
             comm.reply_ok();
             match comm.next_event() {
-                Event::Command(Instruction::DkgRound2 { chunk: 0 }) => {}
+                Event::Command(Instruction::DkgRound2 { chunk: 0, .. }) => {}
                 _ => {},
             }
         }
@@ -26,13 +26,14 @@ use ledger_device_sdk::io::{Comm};
 use crate::accumulator::accumulate_data;
 use crate::nvm::buffer::{Buffer};
 use crate::context::TxContext;
-use crate::handlers::dkg_get_identity::compute_dkg_secret;
+use crate::handlers::dkg_get_identity::{compute_dkg_secret, read_identity_path, IdentityPath};
 use crate::nvm::dkg_keys::DkgKeys;
-use crate::utils::{zlog_stack};
+use crate::nvm::dkg_secrets::{DkgSecrets, SECRETS_ON_DEVICE};
+use crate::utils::zlog_stack;
 
 
 pub struct MinTx {
-    identity_index: u8,
+    identity_path: IdentityPath,
     round_1_packages: Vec<Vec<u8>>,
     round_2_packages: Vec<Vec<u8>>,
     round_2_secret_package: Vec<u8>,
@@ -44,6 +45,7 @@ pub struct MinTx {
 pub fn handler_dkg_round_3(
     comm: &mut Comm,
     chunk: u8,
+    flags: u8,
     ctx: &mut TxContext,
 ) -> Result<(), AppSW> {
     zlog_stack("start handler_dkg_round_3\0");
@@ -54,7 +56,7 @@ pub fn handler_dkg_round_3(
     }
 
     // Try to deserialize the transaction
-    let min_tx = parse_tx_min(&ctx.buffer)?;
+    let min_tx = parse_tx_min(&ctx.buffer, flags)?;
 
     let (key_package, public_key_package, group_secret_key)
         = compute_dkg_round_3_min(&min_tx).map_err(|_| AppSW::DkgRound3Fail)?;
@@ -62,17 +64,19 @@ pub fn handler_dkg_round_3(
 
     DkgKeys.save_keys(key_package, public_key_package, group_secret_key);
 
+    // The ceremony is over, round secrets are not needed anymore
+    if flags & SECRETS_ON_DEVICE != 0 {
+        DkgSecrets.clear_round_secrets();
+    }
+
     Ok(())
 }
 
 #[inline(never)]
-fn parse_tx_min(buffer: &Buffer) -> Result<MinTx, AppSW>{
+fn parse_tx_min(buffer: &Buffer, flags: u8) -> Result<MinTx, AppSW>{
     zlog_stack("start parse_tx_min round3\0");
 
-    let mut tx_pos:usize = 0;
-
-    let identity_index = buffer.get_element(tx_pos)?;
-    tx_pos +=1;
+    let (identity_path, mut tx_pos) = read_identity_path(buffer, 0, flags)?;
 
     // Round 1 public packages
     let elements = buffer.get_element(tx_pos)?;
@@ -110,13 +114,17 @@ fn parse_tx_min(buffer: &Buffer) -> Result<MinTx, AppSW>{
         zlog_stack("done parse_round_2 - e\0");
     }
 
-    // round 2 secret pkg
-    let len = buffer.get_u16(tx_pos)?;
-    tx_pos +=2;
+    // round 2 secret pkg, unless kept on the device
+    let round_2_secret_package = if flags & SECRETS_ON_DEVICE != 0 {
+        DkgSecrets.load_round_2_secret()?.to_vec()
+    } else {
+        let len = buffer.get_u16(tx_pos)?;
+        tx_pos +=2;
 
-    let round_2_secret_package_slice = buffer.get_slice(tx_pos,tx_pos+len)?;
-    let round_2_secret_package = round_2_secret_package_slice.to_vec();
-    tx_pos += len;
+        let round_2_secret_package_slice = buffer.get_slice(tx_pos,tx_pos+len)?;
+        tx_pos += len;
+        round_2_secret_package_slice.to_vec()
+    };
 
     // participants
     let elements = buffer.get_element(tx_pos)?;
@@ -164,7 +172,7 @@ fn parse_tx_min(buffer: &Buffer) -> Result<MinTx, AppSW>{
         round_2_secret_package,
         round_1_packages,
         round_2_packages,
-        identity_index,
+        identity_path,
         participants,
         gsk_bytes,
     })
@@ -174,7 +182,7 @@ fn parse_tx_min(buffer: &Buffer) -> Result<MinTx, AppSW>{
 fn compute_dkg_round_3_min(min_tx: &MinTx) -> Result<(KeyPackage, FrostPublicKeyPackage, GroupSecretKey), IronfishFrostError> {
     zlog_stack("start compute_dkg_round_3\0");
 
-    let secret = compute_dkg_secret(min_tx.identity_index);
+    let secret = compute_dkg_secret(&min_tx.identity_path);
 
     let p = min_tx.participants.iter().map(|p| p.as_slice()).collect();
     let r1 = min_tx.round_1_packages.iter().map(|r| r.as_slice()).collect();
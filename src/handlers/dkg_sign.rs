@@ -21,12 +21,23 @@ use ironfish_frost::{frost::SigningPackage, frost::Randomizer};
 use ironfish_frost::frost::keys::KeyPackage;
 use ironfish_frost::frost::round1::SigningNonces;
 use ironfish_frost::frost::round2;
+use ironfish_frost::frost::round2::SignatureShare;
+use ironfish_frost::frost::{frost, RandomizedParams};
 use ledger_device_sdk::io::{Comm, Event};
 use crate::accumulator::accumulate_data;
 use crate::nvm::buffer::{Buffer};
 use crate::context::TxContext;
 use crate::utils::{zlog_stack};
 use crate::nvm::dkg_keys::DkgKeys;
+use crate::nvm::nonce_log::{nonce_commitments_digest, signed_data_digest, NonceLog};
+use crate::nvm::settings::{Settings, BLIND_SIGNING, EXPERT_MODE};
+use crate::app_ui::sign::{expert_fields, ui_review_blind_signing, ui_review_transaction};
+use crate::ironfish::transaction::Transaction;
+use crate::ironfish::assets::AssetDescriptor;
+use crate::ironfish::note::{value_commitment, Note, NOTE_SIZE};
+use crate::ironfish::shares::{generator_mul, point_to_bytes, scalar_from_bytes, SCALAR_LEN};
+use jubjub::ExtendedPoint;
+use alloc::string::String;
 
 const MAX_APDU_SIZE: usize = 253;
 
@@ -43,7 +54,23 @@ pub fn handler_dkg_sign(
         return Ok(());
     }
 
-    let (frost_signing_package, nonces, randomizer) = parse_tx(&ctx.buffer)?;
+    // Asset descriptors only apply to the transaction signed now
+    let assets = core::mem::take(&mut ctx.assets);
+
+    let (frost_signing_package, nonces, randomizer, randomizer_bytes, raw_tx) = parse_tx(&ctx.buffer)?;
+
+    let details = if Settings.is_enabled(EXPERT_MODE) {
+        expert_fields(randomizer_bytes, &frost_signing_package)
+    } else {
+        Vec::new()
+    };
+
+    review_signing(frost_signing_package.message(), &[randomizer_bytes], raw_tx, &assets, &details)?;
+    drop(assets);
+    drop(details);
+
+    record_nonce_use(&frost_signing_package, &nonces, randomizer_bytes)?;
+
     let key_package = DkgKeys.load_key_package()?;
 
     zlog_stack("start signing\0");
@@ -54,14 +81,252 @@ pub fn handler_dkg_sign(
         randomizer,
     );
 
-    zlog_stack("unwrap sig result\0");
-    let sig = signature.unwrap().serialize();
+    zlog_stack("check sig result\0");
+    let signature = signature.map_err(|_| AppSW::InvalidSigningPackage)?;
+
+    verify_signature_share(&frost_signing_package, &key_package, randomizer_bytes, &signature)?;
+    drop(key_package);
+
+    let sig = signature.serialize();
 
     send_apdu_chunks(comm, sig)
 }
 
+const SPENT_NOTE_LEN: usize = 8 + NOTE_SIZE;
+const OUTPUT_OPENING_LEN: usize = NOTE_SIZE + SCALAR_LEN;
+
+/// Unsigned transaction sent along the signing package, optionally followed by the
+/// notes spent by the transaction, `n | (position (u64 LE) | note) * n`, one for each
+/// spend, and then by the openings of its outputs, `m | (note | value commitment
+/// randomness) * m`, one for each output. Both lists are in transaction order, and an
+/// empty list means it was not provided.
+pub struct TransactionData<'a> {
+    pub raw_tx: &'a [u8],
+    pub notes: &'a [u8],
+    pub outputs: &'a [u8],
+}
+
+/// Reads the optional transaction data at the end of a signing payload
+pub fn read_transaction(buffer: &Buffer, mut tx_pos: usize) -> Result<(Option<TransactionData>, usize), AppSW> {
+    if tx_pos >= buffer.pos {
+        return Ok((None, tx_pos));
+    }
+
+    let tx_len = buffer.get_u16(tx_pos)?;
+    tx_pos +=2;
+
+    let raw_tx = buffer.get_slice(tx_pos,tx_pos+tx_len)?;
+    tx_pos += tx_len;
+
+    let (notes, tx_pos) = read_list(buffer, tx_pos, SPENT_NOTE_LEN)?;
+    let (outputs, tx_pos) = read_list(buffer, tx_pos, OUTPUT_OPENING_LEN)?;
+
+    Ok((Some(TransactionData { raw_tx, notes, outputs }), tx_pos))
+}
+
+/// Reads an optional `n | element * n` list, returning the elements
+fn read_list(buffer: &Buffer, mut pos: usize, element_len: usize) -> Result<(&[u8], usize), AppSW> {
+    if pos >= buffer.pos {
+        return Ok((&[], pos));
+    }
+
+    let count = buffer.get_element(pos)? as usize;
+    pos += 1;
+
+    let elements = buffer.get_slice(pos, pos + count * element_len)?;
+    pos += count * element_len;
+
+    Ok((elements, pos))
+}
+
+/// Spends must be authorized by the stored account: the randomness of the
+/// transaction, of every spend and of every mint has to randomize our authorizing
+/// key into the randomized public key the descriptions are signed for, and so does
+/// every randomizer we sign with. When the spent notes are given, they must be ours
+/// and match the nullifiers of the spends.
+#[inline(never)]
+fn check_spends(tx: &Transaction, randomizers: &[&[u8]], notes: &[u8]) -> Result<(), AppSW> {
+    zlog_stack("start check_spends\0");
+
+    let account_keys = DkgKeys.load_account_keys()?;
+
+    let authorizing_key = ExtendedPoint::from(account_keys.view_key.authorizing_key);
+    let randomizes_to_rk = |randomness: &[u8]| -> Result<bool, AppSW> {
+        let randomizer = scalar_from_bytes(randomness).map_err(|_| AppSW::InvalidRandomizer)?;
+        Ok(point_to_bytes(&(authorizing_key + generator_mul(&randomizer))) == tx.randomized_public_key)
+    };
+
+    let descriptions = tx.spends.iter().map(|spend| &spend.public_key_randomness[..])
+        .chain(tx.mints.iter().map(|mint| &mint.public_key_randomness[..]));
+    for randomness in core::iter::once(&tx.public_key_randomness[..]).chain(descriptions) {
+        if !randomizes_to_rk(randomness)? {
+            return Err(AppSW::SpendNotOwned);
+        }
+    }
+
+    for randomizer_bytes in randomizers.iter() {
+        if !randomizes_to_rk(randomizer_bytes)? {
+            return Err(AppSW::SpendNotOwned);
+        }
+    }
+
+    if notes.is_empty() {
+        return Ok(());
+    }
+
+    if notes.len() != tx.spends.len() * SPENT_NOTE_LEN {
+        return Err(AppSW::InvalidNote);
+    }
+
+    let address = account_keys.public_address.public_address();
+    for (spend, data) in tx.spends.iter().zip(notes.chunks(SPENT_NOTE_LEN)) {
+        let mut position = [0u8; 8];
+        position.copy_from_slice(&data[..8]);
+        let note = Note::read(&data[8..]).map_err(|_| AppSW::InvalidNote)?;
+
+        if note.owner != address
+            || note.nullifier(&account_keys.view_key.nullifier_deriving_key, u64::from_le_bytes(position))
+                .map_err(|_| AppSW::InvalidNote)? != spend.nullifier
+        {
+            return Err(AppSW::SpendNotOwned);
+        }
+    }
+
+    Ok(())
+}
+
+/// Amounts shown to the user must be the committed ones: every output opening has to
+/// match both the note commitment and the value commitment of its output, so the
+/// host cannot report a different recipient, asset or value. Without openings the
+/// outputs are unknown, see [`review_signing`].
+#[inline(never)]
+fn check_outputs(tx: &Transaction, outputs: &[u8]) -> Result<Vec<Note>, AppSW> {
+    zlog_stack("start check_outputs\0");
+
+    if outputs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    if outputs.len() != tx.outputs.len() * OUTPUT_OPENING_LEN {
+        return Err(AppSW::InvalidNote);
+    }
+
+    let mut notes = Vec::with_capacity(tx.outputs.len());
+    for (output, data) in tx.outputs.iter().zip(outputs.chunks(OUTPUT_OPENING_LEN)) {
+        let note = Note::read(&data[..NOTE_SIZE]).map_err(|_| AppSW::InvalidNote)?;
+        let randomness = scalar_from_bytes(&data[NOTE_SIZE..]).map_err(|_| AppSW::InvalidNote)?;
+
+        let value_commitment = value_commitment(note.value, &note.asset_id, &randomness)
+            .map_err(|_| AppSW::InvalidNote)?;
+        if point_to_bytes(&value_commitment) != output.value_commitment
+            || note.commitment().map_err(|_| AppSW::InvalidNote)? != output.note_commitment
+        {
+            return Err(AppSW::OutputCommitmentMismatch);
+        }
+
+        notes.push(note);
+    }
+
+    Ok(notes)
+}
+
+/// Shows the transaction the message is the hash of, or only the message when blind
+/// signing is enabled and there is no transaction, or no openings of its outputs.
+#[inline(never)]
+pub fn review_signing(
+    message: &[u8],
+    randomizers: &[&[u8]],
+    raw_tx: Option<TransactionData>,
+    assets: &[AssetDescriptor],
+    details: &[(String, String)],
+) -> Result<(), AppSW> {
+    match raw_tx {
+        Some(TransactionData { raw_tx, notes, outputs }) => {
+            let tx = Transaction::parse(raw_tx).map_err(|_| AppSW::TxParsingFail)?;
+
+            // The message signed by frost must be the hash of the transaction shown to the user
+            if message != &tx.signature_hash()[..] {
+                return Err(AppSW::TxHashFail);
+            }
+
+            check_spends(&tx, randomizers, notes)?;
+
+            // Recipients and amounts can't be shown without the openings, so the
+            // transaction is no better known than its hash
+            if outputs.is_empty() && !tx.outputs.is_empty() {
+                return review_blind_signing(message, details);
+            }
+
+            let outputs = check_outputs(&tx, outputs)?;
+
+            if !ui_review_transaction(&tx, &outputs, assets, details)? {
+                return Err(AppSW::Deny);
+            }
+        }
+        None => return review_blind_signing(message, details),
+    }
+
+    Ok(())
+}
+
+/// Only the hash is known, which is only accepted when the user opted in
+fn review_blind_signing(message: &[u8], details: &[(String, String)]) -> Result<(), AppSW> {
+    if !Settings.is_enabled(BLIND_SIGNING) {
+        return Err(AppSW::BlindSigningDisabled);
+    }
+
+    if !ui_review_blind_signing(message, details) {
+        return Err(AppSW::Deny);
+    }
+
+    Ok(())
+}
+
+/// The same nonces must never sign different data, the log keeps what the last ones
+/// were used for.
+#[inline(never)]
+pub fn record_nonce_use(
+    signing_package: &SigningPackage,
+    nonces: &SigningNonces,
+    randomizer_bytes: &[u8],
+) -> Result<(), AppSW> {
+    zlog_stack("start record_nonce_use\0");
+
+    let commitments = nonces.commitments().serialize().map_err(|_| AppSW::InvalidSigningNonces)?;
+    let nonce_commitments = nonce_commitments_digest(&commitments);
+
+    let signing_package_vec = signing_package.serialize().map_err(|_| AppSW::InvalidSigningPackage)?;
+    let signed_data = signed_data_digest(&signing_package_vec, randomizer_bytes);
+
+    NonceLog.check_and_record(&nonce_commitments, &signed_data)
+}
+
+/// Our share is checked like the coordinator would, against our verifying share and
+/// the randomized group key, so a share corrupted while computing it never leaves the
+/// device.
+#[inline(never)]
+pub fn verify_signature_share(
+    signing_package: &SigningPackage,
+    key_package: &KeyPackage,
+    randomizer_bytes: &[u8],
+    signature_share: &SignatureShare,
+) -> Result<(), AppSW> {
+    zlog_stack("start verify_signature_share\0");
+
+    let randomizer = Randomizer::deserialize(randomizer_bytes).map_err(|_| AppSW::InvalidRandomizer)?;
+    let randomized_params = RandomizedParams::from_randomizer(key_package.verifying_key(), randomizer);
+
+    frost::verify_signature_share(
+        *key_package.identifier(),
+        key_package.verifying_share(),
+        signature_share,
+        signing_package,
+        randomized_params.randomized_verifying_key(),
+    ).map_err(|_| AppSW::InvalidSignatureShare)
+}
+
 #[inline(never)]
-fn parse_tx(buffer: &Buffer) -> Result<(SigningPackage, SigningNonces, Randomizer), AppSW>{
+fn parse_tx(buffer: &Buffer) -> Result<(SigningPackage, SigningNonces, Randomizer, &[u8], Option<TransactionData>), AppSW>{
     zlog_stack("start parse_tx\0");
 
     let mut tx_pos = 0;
@@ -69,8 +334,8 @@ fn parse_tx(buffer: &Buffer) -> Result<(SigningPackage, SigningNonces, Randomize
     let pk_randomness_len = buffer.get_u16(tx_pos)?;
     tx_pos +=2;
 
-    let data = buffer.get_slice(tx_pos,tx_pos+pk_randomness_len)?;
-    let randomizer = Randomizer::deserialize(data).map_err(|_| AppSW::InvalidRandomizer)?;
+    let randomizer_bytes = buffer.get_slice(tx_pos,tx_pos+pk_randomness_len)?;
+    let randomizer = Randomizer::deserialize(randomizer_bytes).map_err(|_| AppSW::InvalidRandomizer)?;
     tx_pos +=pk_randomness_len;
 
     let frost_signing_package_len = buffer.get_u16(tx_pos)?;
@@ -87,11 +352,14 @@ fn parse_tx(buffer: &Buffer) -> Result<(SigningPackage, SigningNonces, Randomize
     let nonces = SigningNonces::deserialize(data).map_err(|_| AppSW::InvalidSigningNonces)?;
     tx_pos += nonces_len;
 
+    // Optionally, the unsigned transaction the signing package was built from
+    let (raw_tx, tx_pos) = read_transaction(buffer, tx_pos)?;
+
     if tx_pos != buffer.pos {
         return Err(AppSW::InvalidPayload);
     }
 
-    Ok((frost_signing_package, nonces, randomizer))
+    Ok((frost_signing_package, nonces, randomizer, randomizer_bytes, raw_tx))
 }
 
 #[inline(never)]
@@ -0,0 +1,196 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::{AppSW, Instruction};
+use alloc::string::String;
+use alloc::vec::Vec;
+use ironfish_frost::frost::{SigningPackage, Randomizer};
+use ironfish_frost::frost::round1::SigningNonces;
+use ironfish_frost::frost::round2;
+use ledger_device_sdk::io::{Comm, Event};
+use crate::accumulator::accumulate_data;
+use crate::app_ui::sign::expert_fields;
+use crate::context::TxContext;
+use crate::handlers::dkg_sign::{
+    read_transaction, record_nonce_use, review_signing, verify_signature_share, TransactionData,
+};
+use crate::nvm::buffer::Buffer;
+use crate::nvm::dkg_keys::DkgKeys;
+use crate::nvm::settings::{Settings, EXPERT_MODE};
+use crate::utils::{zlog_stack};
+
+const MAX_APDU_SIZE: usize = 253;
+
+/// One signature of the batch. Every signature needs its own nonces, as a share
+/// computed twice with the same nonces for different randomizers leaks the signing
+/// share, so each entry comes with its own signing package. The nonces of entry `i`
+/// are the ones DkgNonces returns when given the entry index `i`.
+pub struct BatchEntry<'a> {
+    signing_package: SigningPackage,
+    nonces: SigningNonces,
+    randomizer_bytes: &'a [u8],
+}
+
+/// Signs every spend of a transaction after a single review. The payload is
+/// `n | (randomizer | signing package | nonces) * n | transaction`, each element
+/// prefixed by its length, the transaction, spent notes and output openings being
+/// optional as for a single signature.
+/// All the signing packages must be for the same transaction hash, and each
+/// randomizer must be the randomness of the transaction or of one of its spends.
+///
+/// The response is `n | signature shares`, in the order of the entries.
+#[inline(never)]
+pub fn handler_dkg_sign_batch(
+    comm: &mut Comm,
+    chunk: u8,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    zlog_stack("start handler_dkg_sign_batch\0");
+
+    accumulate_data(comm, chunk, ctx)?;
+    if !ctx.done {
+        return Ok(());
+    }
+
+    // Asset descriptors only apply to the transaction signed now
+    let assets = core::mem::take(&mut ctx.assets);
+
+    let (entries, raw_tx) = parse_tx(&ctx.buffer)?;
+    check_entries(&entries)?;
+
+    let details = if Settings.is_enabled(EXPERT_MODE) {
+        let mut details: Vec<(String, String)> = Vec::new();
+        for entry in entries.iter() {
+            details.extend(expert_fields(entry.randomizer_bytes, &entry.signing_package));
+        }
+        details
+    } else {
+        Vec::new()
+    };
+
+    let randomizers: Vec<&[u8]> = entries.iter().map(|entry| entry.randomizer_bytes).collect();
+    review_signing(entries[0].signing_package.message(), &randomizers, raw_tx, &assets, &details)?;
+    drop(assets);
+    drop(details);
+
+    for entry in entries.iter() {
+        record_nonce_use(&entry.signing_package, &entry.nonces, entry.randomizer_bytes)?;
+    }
+
+    let key_package = DkgKeys.load_key_package()?;
+
+    // Nothing is sent until every share was computed and checked
+    let mut resp: Vec<u8> = Vec::new();
+    resp.push(entries.len() as u8);
+    for entry in entries.iter() {
+        zlog_stack("start signing batch entry\0");
+        let randomizer = Randomizer::deserialize(entry.randomizer_bytes).map_err(|_| AppSW::InvalidRandomizer)?;
+        let signature = round2::sign(
+            &entry.signing_package,
+            &entry.nonces,
+            &key_package,
+            randomizer,
+        ).map_err(|_| AppSW::InvalidSigningPackage)?;
+
+        verify_signature_share(&entry.signing_package, &key_package, entry.randomizer_bytes, &signature)?;
+        resp.extend_from_slice(&signature.serialize());
+    }
+    drop(key_package);
+
+    send_apdu_chunks(comm, resp.as_slice())
+}
+
+#[inline(never)]
+fn parse_tx(buffer: &Buffer) -> Result<(Vec<BatchEntry>, Option<TransactionData>), AppSW>{
+    zlog_stack("start parse_tx batch\0");
+
+    let mut tx_pos = 0;
+
+    let elements = buffer.get_element(tx_pos)?;
+    tx_pos += 1;
+
+    let mut entries = Vec::with_capacity(elements as usize);
+    for _i in 0..elements {
+        let len = buffer.get_u16(tx_pos)?;
+        tx_pos += 2;
+
+        let randomizer_bytes = buffer.get_slice(tx_pos, tx_pos+len)?;
+        Randomizer::deserialize(randomizer_bytes).map_err(|_| AppSW::InvalidRandomizer)?;
+        tx_pos += len;
+
+        let len = buffer.get_u16(tx_pos)?;
+        tx_pos += 2;
+
+        let data = buffer.get_slice(tx_pos, tx_pos+len)?;
+        let signing_package = SigningPackage::deserialize(data).map_err(|_| AppSW::InvalidSigningPackage)?;
+        tx_pos += len;
+
+        let len = buffer.get_u16(tx_pos)?;
+        tx_pos += 2;
+
+        let data = buffer.get_slice(tx_pos, tx_pos+len)?;
+        let nonces = SigningNonces::deserialize(data).map_err(|_| AppSW::InvalidSigningNonces)?;
+        tx_pos += len;
+
+        entries.push(BatchEntry { signing_package, nonces, randomizer_bytes });
+    }
+
+    // Optionally, the unsigned transaction the signing packages were built from
+    let (raw_tx, tx_pos) = read_transaction(buffer, tx_pos)?;
+
+    if tx_pos != buffer.pos {
+        return Err(AppSW::InvalidPayload);
+    }
+
+    Ok((entries, raw_tx))
+}
+
+/// One review covers the batch only if every entry signs the same transaction, and
+/// no two entries may share nonces
+fn check_entries(entries: &[BatchEntry]) -> Result<(), AppSW> {
+    let first = entries.first().ok_or(AppSW::InvalidPayload)?;
+
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.signing_package.message() != first.signing_package.message() {
+            return Err(AppSW::TxHashFail);
+        }
+
+        if entries[..i].iter().any(|e| e.nonces.commitments() == entry.nonces.commitments()) {
+            return Err(AppSW::NonceReuse);
+        }
+    }
+
+    Ok(())
+}
+
+fn send_apdu_chunks(comm: &mut Comm, data: &[u8]) -> Result<(), AppSW> {
+    let total_chunks = (data.len() + MAX_APDU_SIZE - 1) / MAX_APDU_SIZE;
+
+    for (i, chunk) in data.chunks(MAX_APDU_SIZE).enumerate() {
+        comm.append(chunk);
+
+        if i < total_chunks - 1 {
+            comm.reply_ok();
+            match comm.next_event() {
+                Event::Command(Instruction::DkgSignBatch { chunk: 0 }) => {}
+                _ => {},
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::AppSW;
+use crate::nvm::settings::Settings;
+use ledger_device_sdk::io;
+
+/// Returns the value of every setting, one byte each, in the order of their index.
+#[inline(never)]
+pub fn handler_get_settings(comm: &mut io::Comm) -> Result<(), AppSW> {
+    comm.append(&Settings.get_all());
+    Ok(())
+}
@@ -0,0 +1,57 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::AppSW;
+use ledger_device_sdk::ecc::{CurvesId, ECPublicKey};
+use ledger_device_sdk::io::Comm;
+use crate::context::TxContext;
+use crate::ironfish::assets::AssetDescriptor;
+use crate::nvm::asset_key::AssetKey;
+use crate::utils::{zlog_stack};
+
+const MAX_ASSET_DESCRIPTORS: usize = 8;
+
+/// Keeps the metadata of an asset for the review of the next transaction. The
+/// descriptor must be signed by the key the user installed, see
+/// [`handler_set_asset_key`](crate::handlers::set_asset_key::handler_set_asset_key).
+#[inline(never)]
+pub fn handler_provide_asset_descriptor(
+    comm: &mut Comm,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    zlog_stack("start handler_provide_asset_descriptor\0");
+
+    let data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
+
+    let (descriptor, digest, signature)
+        = AssetDescriptor::parse(data).map_err(|_| AppSW::InvalidAssetDescriptor)?;
+
+    let mut public_key = ECPublicKey::<65, 'W'>::new(CurvesId::Secp256k1);
+    public_key.pubkey = AssetKey.load().ok_or(AppSW::InvalidAssetDescriptor)?;
+    if !public_key.verify((signature, signature.len() as u32), &digest) {
+        return Err(AppSW::InvalidAssetDescriptor);
+    }
+
+    // Replace any previous descriptor for the same asset
+    ctx.assets.retain(|a| a.id != descriptor.id);
+    if ctx.assets.len() >= MAX_ASSET_DESCRIPTORS {
+        return Err(AppSW::InvalidAssetDescriptor);
+    }
+    ctx.assets.push(descriptor);
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+/*****************************************************************************
+ *   Ledger App Ironfish Rust.
+ *   (c) 2023 Ledger SAS.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::AppSW;
+use ledger_device_sdk::io::Comm;
+use crate::app_ui::sign::ui_confirm_asset_key;
+use crate::ironfish::assets::{asset_key_fingerprint, ASSET_KEY_LEN};
+use crate::nvm::asset_key::AssetKey;
+use crate::utils::{zlog_stack};
+
+/// Installs the key signing asset descriptors, replacing the previous one. The
+/// payload is the uncompressed secp256k1 key. The user confirms its fingerprint,
+/// which has to match the one published by the signer of the asset list.
+#[inline(never)]
+pub fn handler_set_asset_key(comm: &mut Comm) -> Result<(), AppSW> {
+    zlog_stack("start handler_set_asset_key\0");
+
+    let data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
+    let key = <&[u8; ASSET_KEY_LEN]>::try_from(data).map_err(|_| AppSW::InvalidAssetDescriptor)?;
+
+    if !ui_confirm_asset_key(&asset_key_fingerprint(key)) {
+        return Err(AppSW::Deny);
+    }
+
+    AssetKey.save(key)
+}
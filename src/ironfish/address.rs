@@ -0,0 +1,93 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Human readable forms of public addresses.
+//!
+//! The canonical form is the one used by the node and the wallets: the 32 bytes of
+//! the address as lowercase hex, without prefix. The short form keeps both ends of
+//! it and adds a fingerprint of the whole address, so that changing any byte of
+//! the middle still shows.
+
+use alloc::format;
+use alloc::string::String;
+use blake2b_simd::Params as Blake2b;
+use crate::ironfish::constants::ADDRESS_FINGERPRINT_PERSONALIZATION;
+use crate::ironfish::public_address::PUBLIC_ADDRESS_SIZE;
+
+/// Number of hex characters kept on each side of a short address
+pub const SHORT_ADDRESS_CHARS: usize = 8;
+
+/// Bytes of the address hash shown as its fingerprint
+pub const ADDRESS_FINGERPRINT_LEN: usize = 4;
+
+/// Canonical hex form of an address
+pub fn encode_address(address: &[u8; PUBLIC_ADDRESS_SIZE]) -> String {
+    hex::encode(address)
+}
+
+/// Hex of `BLAKE2b-32("IFAddressFingerp", address)`, a checksum of every byte of
+/// the address that wallets can compute to compare with the device
+pub fn address_fingerprint(address: &[u8; PUBLIC_ADDRESS_SIZE]) -> String {
+    let hash = Blake2b::new()
+        .hash_length(ADDRESS_FINGERPRINT_LEN)
+        .personal(ADDRESS_FINGERPRINT_PERSONALIZATION)
+        .hash(address);
+
+    hex::encode(hash.as_bytes())
+}
+
+/// Start and end of the canonical form followed by the fingerprint of the address,
+/// as `start...end (fingerprint)`
+pub fn short_address(address: &[u8; PUBLIC_ADDRESS_SIZE]) -> String {
+    let encoded = encode_address(address);
+
+    format!(
+        "{}...{} ({})",
+        &encoded[..SHORT_ADDRESS_CHARS],
+        &encoded[encoded.len() - SHORT_ADDRESS_CHARS..],
+        address_fingerprint(address)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ledger_device_sdk::assert_eq_err as assert_eq;
+    use ledger_device_sdk::testing::TestType;
+    use testmacro::test_item as test;
+
+    const ADDRESS: [u8; PUBLIC_ADDRESS_SIZE] = [
+        0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef,
+        0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10, 0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10,
+    ];
+
+    #[test]
+    fn encode_address_is_lowercase_hex() {
+        assert_eq!(
+            encode_address(&ADDRESS).as_str(),
+            "0123456789abcdef0123456789abcdeffedcba9876543210fedcba9876543210"
+        );
+    }
+
+    #[test]
+    fn short_address_keeps_both_ends() {
+        let short = short_address(&ADDRESS);
+        let expected = format!("01234567...76543210 ({})", address_fingerprint(&ADDRESS));
+        assert_eq!(short, expected);
+    }
+
+    #[test]
+    fn address_fingerprint_known_answer() {
+        assert_eq!(address_fingerprint(&ADDRESS).as_str(), "e5b1216d");
+    }
+
+    #[test]
+    fn address_fingerprint_covers_the_middle() {
+        // Same start and end, so only the fingerprint tells the addresses apart
+        let mut other = ADDRESS;
+        other[PUBLIC_ADDRESS_SIZE / 2] ^= 1;
+        assert_eq!(short_address(&other) == short_address(&ADDRESS), false);
+        assert_eq!(address_fingerprint(&other).len(), 2 * ADDRESS_FINGERPRINT_LEN);
+    }
+}
@@ -0,0 +1,225 @@
+//! Asset metadata used to display amounts in a human readable form.
+//!
+//! The native asset is compiled into the app. Other assets are described by the
+//! host with an [`AssetDescriptor`], which is only accepted when signed by the
+//! asset descriptor key the user installed on the device. Any asset without
+//! metadata is shown in base units, flagged as unknown.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use blake2b_simd::Params as Blake2b;
+use crate::ironfish::constants::{ASSET_KEY_FINGERPRINT_PERSONALIZATION, NATIVE_ASSET};
+use crate::ironfish::errors::IronfishError;
+use crate::ironfish::transaction::ASSET_ID_LENGTH;
+
+/// BLAKE2b Personalization for the digest signed in an asset descriptor
+const ASSET_DESCRIPTOR_PERSONALIZATION: &[u8; 16] = b"Ironfish AssetMD";
+
+pub const MAX_TICKER_LEN: usize = 10;
+pub const MAX_DECIMALS: u8 = 18;
+
+/// Uncompressed secp256k1 public key signing asset descriptors
+pub const ASSET_KEY_LEN: usize = 65;
+
+/// Bytes of the key hash shown when the key is installed
+const ASSET_KEY_FINGERPRINT_LEN: usize = 8;
+
+pub struct KnownAsset {
+    pub id: [u8; ASSET_ID_LENGTH],
+    pub ticker: &'static str,
+    pub decimals: u8,
+}
+
+/// Assets always available without a descriptor from the host
+pub const KNOWN_ASSETS: [KnownAsset; 1] = [
+    KnownAsset { id: NATIVE_ASSET, ticker: "IRON", decimals: 8 },
+];
+
+/// Asset metadata provided by the host, signed by the installed asset descriptor key
+pub struct AssetDescriptor {
+    pub id: [u8; ASSET_ID_LENGTH],
+    pub ticker: String,
+    pub decimals: u8,
+}
+
+impl AssetDescriptor {
+    /// Parse a serialized descriptor.
+    ///
+    /// Layout: `asset_id (32) | ticker_len (1) | ticker | decimals (1) | signature (DER)`.
+    /// Returns the descriptor, the digest to be checked and the signature.
+    pub fn parse(data: &[u8]) -> Result<(Self, [u8; 32], &[u8]), IronfishError> {
+        if data.len() < ASSET_ID_LENGTH + 1 {
+            return Err(IronfishError::InvalidData);
+        }
+
+        let mut id = [0u8; ASSET_ID_LENGTH];
+        id.copy_from_slice(&data[..ASSET_ID_LENGTH]);
+        let mut pos = ASSET_ID_LENGTH;
+
+        let ticker_len = data[pos] as usize;
+        pos += 1;
+        if ticker_len == 0 || ticker_len > MAX_TICKER_LEN || data.len() < pos + ticker_len + 1 {
+            return Err(IronfishError::InvalidData);
+        }
+
+        let ticker_bytes = &data[pos..pos + ticker_len];
+        if !ticker_bytes.iter().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(IronfishError::InvalidData);
+        }
+        let ticker = String::from(core::str::from_utf8(ticker_bytes).map_err(|_| IronfishError::Utf8)?);
+        pos += ticker_len;
+
+        let decimals = data[pos];
+        pos += 1;
+        if decimals > MAX_DECIMALS {
+            return Err(IronfishError::InvalidData);
+        }
+
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(
+            Blake2b::new()
+                .hash_length(32)
+                .personal(ASSET_DESCRIPTOR_PERSONALIZATION)
+                .hash(&data[..pos])
+                .as_bytes(),
+        );
+
+        let signature = &data[pos..];
+        if signature.is_empty() {
+            return Err(IronfishError::InvalidSignature);
+        }
+
+        Ok((AssetDescriptor { id, ticker, decimals }, digest, signature))
+    }
+}
+
+/// Short hash of the asset descriptor key, for the user to compare with the one
+/// published by the signer of the asset list before installing the key
+pub fn asset_key_fingerprint(key: &[u8; ASSET_KEY_LEN]) -> String {
+    let hash = Blake2b::new()
+        .hash_length(ASSET_KEY_FINGERPRINT_LEN)
+        .personal(ASSET_KEY_FINGERPRINT_PERSONALIZATION)
+        .hash(key);
+
+    hex::encode(hash.as_bytes())
+}
+
+pub struct AssetInfo<'a> {
+    pub ticker: &'a str,
+    pub decimals: u8,
+}
+
+/// Look for the metadata of an asset, first in the compiled table and then in the
+/// descriptors provided by the host.
+pub fn find_asset<'a>(id: &[u8; ASSET_ID_LENGTH], descriptors: &'a [AssetDescriptor]) -> Option<AssetInfo<'a>> {
+    if let Some(known) = KNOWN_ASSETS.iter().find(|a| a.id == *id) {
+        return Some(AssetInfo { ticker: known.ticker, decimals: known.decimals });
+    }
+
+    descriptors
+        .iter()
+        .find(|d| d.id == *id)
+        .map(|d| AssetInfo { ticker: d.ticker.as_str(), decimals: d.decimals })
+}
+
+/// Format a value in base units using the given number of decimals, e.g. `1250`
+/// with 2 decimals is `12.50`. Trailing zeros are trimmed down to two decimals.
+pub fn format_amount(value: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return format!("{}", value);
+    }
+
+    let divisor = 10u64.pow(decimals as u32);
+    let mut fraction: Vec<u8> = format!("{:0width$}", value % divisor, width = decimals as usize).into_bytes();
+    while fraction.len() > 2 && fraction.last() == Some(&b'0') {
+        fraction.pop();
+    }
+
+    format!("{}.{}", value / divisor, String::from_utf8(fraction).unwrap_or_default())
+}
+
+/// Amount shown on screen. Unknown assets are shown in base units and flagged, so
+/// the review can warn the user.
+pub fn display_amount(id: &[u8; ASSET_ID_LENGTH], value: u64, descriptors: &[AssetDescriptor]) -> (String, bool) {
+    match find_asset(id, descriptors) {
+        Some(asset) => (format!("{} {}", asset.ticker, format_amount(value, asset.decimals)), true),
+        None => (format!("{} base units", value), false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ledger_device_sdk::assert_eq_err as assert_eq;
+    use ledger_device_sdk::testing::TestType;
+    use testmacro::test_item as test;
+
+    fn descriptor_bytes(ticker: &[u8], decimals: u8) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&[7u8; ASSET_ID_LENGTH]);
+        data.push(ticker.len() as u8);
+        data.extend_from_slice(ticker);
+        data.push(decimals);
+        data.extend_from_slice(&[0x30, 0x44]);
+        data
+    }
+
+    #[test]
+    fn format_amount_keeps_two_decimals() {
+        assert_eq!(format_amount(1250, 2), "12.50");
+        assert_eq!(format_amount(100000000, 8), "1.00");
+        assert_eq!(format_amount(5, 8), "0.00000005");
+        assert_eq!(format_amount(123450000, 8), "1.2345");
+    }
+
+    #[test]
+    fn format_amount_without_decimals() {
+        assert_eq!(format_amount(0, 0), "0");
+        assert_eq!(format_amount(u64::MAX, 0), "18446744073709551615");
+    }
+
+    #[test]
+    fn format_amount_max_decimals() {
+        assert_eq!(format_amount(u64::MAX, 18), "18.446744073709551615");
+    }
+
+    #[test]
+    fn display_amount_of_known_and_unknown_assets() {
+        assert_eq!(display_amount(&NATIVE_ASSET, 150000000, &[]), (String::from("IRON 1.50"), true));
+        assert_eq!(display_amount(&[1u8; ASSET_ID_LENGTH], 42, &[]), (String::from("42 base units"), false));
+    }
+
+    #[test]
+    fn display_amount_of_described_assets() {
+        let (descriptor, _, _) = AssetDescriptor::parse(&descriptor_bytes(b"USDC", 6)).unwrap();
+        let descriptors = [descriptor];
+
+        assert_eq!(display_amount(&[7u8; ASSET_ID_LENGTH], 12500000, &descriptors), (String::from("USDC 12.50"), true));
+        assert_eq!(display_amount(&[1u8; ASSET_ID_LENGTH], 42, &descriptors), (String::from("42 base units"), false));
+    }
+
+    #[test]
+    fn descriptor_digest_covers_the_metadata() {
+        let data = descriptor_bytes(b"USDC", 6);
+        let (descriptor, digest, signature) = AssetDescriptor::parse(&data).unwrap();
+        assert_eq!(descriptor.ticker.as_str(), "USDC");
+        assert_eq!(descriptor.decimals, 6);
+        assert_eq!(signature, &[0x30, 0x44]);
+
+        let (_, other_digest, _) = AssetDescriptor::parse(&descriptor_bytes(b"USDC", 8)).unwrap();
+        assert_eq!(digest == other_digest, false);
+    }
+
+    #[test]
+    fn descriptor_rejects_invalid_metadata() {
+        assert_eq!(AssetDescriptor::parse(&descriptor_bytes(b"", 6)).is_err(), true);
+        assert_eq!(AssetDescriptor::parse(&descriptor_bytes(b"US DC", 6)).is_err(), true);
+        assert_eq!(AssetDescriptor::parse(&descriptor_bytes(b"ABCDEFGHIJK", 6)).is_err(), true);
+        assert_eq!(AssetDescriptor::parse(&descriptor_bytes(b"USDC", MAX_DECIMALS + 1)).is_err(), true);
+
+        let mut unsigned = descriptor_bytes(b"USDC", 6);
+        unsigned.truncate(unsigned.len() - 2);
+        assert_eq!(AssetDescriptor::parse(&unsigned).is_err(), true);
+    }
+}
@@ -50,4 +50,55 @@ pub const PUBLIC_KEY_GENERATOR: AffineNielsPoint = AffinePoint::from_raw_uncheck
 
     .to_niels();
 /// BLAKE2s Personalization for CRH^ivk = BLAKE2s(ak | nk)
-pub const CRH_IVK_PERSONALIZATION: &[u8; 8] = b"Zcashivk";
\ No newline at end of file
+pub const CRH_IVK_PERSONALIZATION: &[u8; 8] = b"Zcashivk";
+/// First 64 bytes of the BLAKE2s input during group hash.
+/// This is chosen to be some random string that we couldn't have anticipated when we designed
+/// the algorithm, for rigidity purposes.
+pub const GH_FIRST_BLOCK: &[u8; 64] = b"096b36a5804bfacef1691e173c366a47ff5ba84a44f26ddd7e8d9f79d5b42df0";
+
+/// BLAKE2s Personalization for deriving asset identifier from asset name
+pub const ASSET_ID_PERSONALIZATION: &[u8; 8] = b"ironf_A_";
+
+/// BLAKE2b Personalization for the transaction signature hash
+pub const SIGNATURE_HASH_PERSONALIZATION: &[u8; 8] = b"IFsighsh";
+
+/// Version of the transaction signature hash
+pub const TRANSACTION_SIGNATURE_VERSION: &[u8; 1] = &[0];
+
+/// Identifier of the native asset ($IRON)
+pub const NATIVE_ASSET: [u8; 32] = [
+    0x51, 0xf3, 0x3a, 0x2f, 0x14, 0xf9, 0x27, 0x35, 0xe5, 0x62, 0xdc, 0x65, 0x8a, 0x56, 0x39, 0x27,
+    0x9d, 0xdc, 0xa3, 0xd5, 0x07, 0x9a, 0x6d, 0x12, 0x42, 0xb2, 0xa5, 0x88, 0xa9, 0xcb, 0xf4, 0x4c,
+];
+
+/// Prefix of the message signed to prove possession of a DKG identity
+pub const IDENTITY_PROOF_DOMAIN: &[u8; 23] = b"Ironfish identity proof";
+
+/// Prefix of the message signed by each participant to confirm the account they got
+/// at the end of a reshare or refresh
+pub const ACCOUNT_CONFIRMATION_DOMAIN: &[u8; 29] = b"Ironfish account confirmation";
+
+/// BLAKE2b personalization for the short fingerprint shown for each DKG identity
+pub const IDENTITY_FINGERPRINT_PERSONALIZATION: &[u8; 16] = b"IFIdentityFinger";
+
+/// BLAKE2b personalization for the fingerprint of a public address
+pub const ADDRESS_FINGERPRINT_PERSONALIZATION: &[u8; 16] = b"IFAddressFingerp";
+
+/// BLAKE2b personalization for the fingerprint of the asset descriptor signing key
+pub const ASSET_KEY_FINGERPRINT_PERSONALIZATION: &[u8; 16] = b"IFAssetKeyFinger";
+
+/// BLAKE2s personalization for the Pedersen hash generators
+pub const PEDERSEN_HASH_GENERATORS_PERSONALIZATION: &[u8; 8] = b"Zcash_PH";
+
+/// BLAKE2s personalization for the generator used to mix the note position in the
+/// nullifier
+pub const NULLIFIER_POSITION_IN_TREE_GENERATOR_PERSONALIZATION: &[u8; 8] = b"Zcash_J_";
+
+/// BLAKE2s personalization for PRF^nf = BLAKE2s(nk | rho)
+pub const PRF_NF_PERSONALIZATION: &[u8; 8] = b"Zcash_nf";
+
+/// BLAKE2s personalization for the value commitment generator of an asset
+pub const VALUE_COMMITMENT_GENERATOR_PERSONALIZATION: &[u8; 8] = b"ironf_cv";
+
+/// BLAKE2s personalization for the value commitment randomness generator
+pub const VALUE_COMMITMENT_RANDOMNESS_GENERATOR_PERSONALIZATION: &[u8; 8] = b"Zcash_cv";
@@ -0,0 +1,412 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Notes and the values derived from them: the note commitment, a Pedersen hash of
+//! the note contents, and the nullifier that marks a note as spent.
+
+use blake2s_simd::Params as Blake2s;
+use chacha20poly1305::aead::AeadInPlace;
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce, Tag};
+use jubjub::{AffinePoint, ExtendedPoint, Fr};
+use crate::ironfish::constants::{
+    GH_FIRST_BLOCK, NULLIFIER_POSITION_IN_TREE_GENERATOR_PERSONALIZATION,
+    PEDERSEN_HASH_GENERATORS_PERSONALIZATION, PRF_NF_PERSONALIZATION,
+    VALUE_COMMITMENT_GENERATOR_PERSONALIZATION, VALUE_COMMITMENT_RANDOMNESS_GENERATOR_PERSONALIZATION,
+};
+use crate::ironfish::errors::IronfishError;
+use crate::ironfish::public_address::PUBLIC_ADDRESS_SIZE;
+use crate::ironfish::transaction::ENCRYPTED_NOTE_WITH_MAC_SIZE;
+use crate::ironfish::view_keys::IncomingViewKey;
+
+pub const MEMO_SIZE: usize = 32;
+pub const NOTE_SIZE: usize = PUBLIC_ADDRESS_SIZE + 32 + 8 + 32 + MEMO_SIZE + PUBLIC_ADDRESS_SIZE;
+pub const NULLIFIER_SIZE: usize = 32;
+/// `randomness | value | memo | asset id | sender`, the owner being implied by the key
+pub const NOTE_CIPHERTEXT_SIZE: usize = 32 + 8 + MEMO_SIZE + 32 + PUBLIC_ADDRESS_SIZE;
+pub const NOTE_ENCRYPTION_MAC_SIZE: usize = 16;
+pub const NOTE_COMMITMENT_SIZE: usize = 32;
+
+/// Chunks of 3 bits hashed with each Pedersen hash generator
+const PEDERSEN_HASH_CHUNKS_PER_GENERATOR: usize = 63;
+
+/// Personalization bits of the note commitment Pedersen hash
+const NOTE_COMMITMENT_PERSONALIZATION_BITS: [bool; 6] = [true; 6];
+
+/// A note, as serialized by the node: `owner | asset id | value | randomness | memo |
+/// sender`, the value being little endian.
+pub struct Note {
+    pub owner: [u8; PUBLIC_ADDRESS_SIZE],
+    pub asset_id: [u8; 32],
+    pub value: u64,
+    pub randomness: Fr,
+    pub memo: [u8; MEMO_SIZE],
+    pub sender: [u8; PUBLIC_ADDRESS_SIZE],
+}
+
+impl Note {
+    pub fn read(bytes: &[u8]) -> Result<Note, IronfishError> {
+        if bytes.len() != NOTE_SIZE {
+            return Err(IronfishError::InvalidData);
+        }
+
+        let mut owner = [0u8; PUBLIC_ADDRESS_SIZE];
+        owner.copy_from_slice(&bytes[0..32]);
+        let mut asset_id = [0u8; 32];
+        asset_id.copy_from_slice(&bytes[32..64]);
+        let mut value = [0u8; 8];
+        value.copy_from_slice(&bytes[64..72]);
+        let mut randomness = [0u8; 32];
+        randomness.copy_from_slice(&bytes[72..104]);
+        let mut memo = [0u8; MEMO_SIZE];
+        memo.copy_from_slice(&bytes[104..136]);
+        let mut sender = [0u8; PUBLIC_ADDRESS_SIZE];
+        sender.copy_from_slice(&bytes[136..168]);
+
+        let randomness: Fr = Option::from(Fr::from_bytes(&randomness)).ok_or(IronfishError::InvalidFr)?;
+
+        Ok(Note {
+            owner,
+            asset_id,
+            value: u64::from_le_bytes(value),
+            randomness,
+            memo,
+            sender,
+        })
+    }
+
+    /// Decrypt a note sent to the owner of `incoming_view_key`, `shared_secret` being
+    /// the secret shared with the sender. Fails if the note was not sent to this key.
+    pub fn from_owner_encrypted(
+        incoming_view_key: &IncomingViewKey,
+        shared_secret: &[u8; 32],
+        encrypted_bytes: &[u8],
+    ) -> Result<Note, IronfishError> {
+        if encrypted_bytes.len() != ENCRYPTED_NOTE_WITH_MAC_SIZE {
+            return Err(IronfishError::InvalidData);
+        }
+
+        let mut plaintext = [0u8; NOTE_CIPHERTEXT_SIZE];
+        plaintext.copy_from_slice(&encrypted_bytes[..NOTE_CIPHERTEXT_SIZE]);
+
+        // Every note is encrypted with a fresh key, so the nonce is always zero
+        ChaCha20Poly1305::new(Key::from_slice(shared_secret))
+            .decrypt_in_place_detached(
+                &Nonce::default(),
+                &[],
+                &mut plaintext,
+                Tag::from_slice(&encrypted_bytes[NOTE_CIPHERTEXT_SIZE..]),
+            )
+            .map_err(|_| IronfishError::InvalidDecryptionKey)?;
+
+        let mut randomness = [0u8; 32];
+        randomness.copy_from_slice(&plaintext[0..32]);
+        let mut value = [0u8; 8];
+        value.copy_from_slice(&plaintext[32..40]);
+        let mut memo = [0u8; MEMO_SIZE];
+        memo.copy_from_slice(&plaintext[40..72]);
+        let mut asset_id = [0u8; 32];
+        asset_id.copy_from_slice(&plaintext[72..104]);
+        let mut sender = [0u8; PUBLIC_ADDRESS_SIZE];
+        sender.copy_from_slice(&plaintext[104..136]);
+
+        let randomness: Fr = Option::from(Fr::from_bytes(&randomness)).ok_or(IronfishError::InvalidFr)?;
+
+        Ok(Note {
+            owner: incoming_view_key.public_address().public_address(),
+            asset_id,
+            value: u64::from_le_bytes(value),
+            randomness,
+            memo,
+            sender,
+        })
+    }
+
+    /// Commitment to the note contents, before the u coordinate is extracted. Fails
+    /// if the asset id has no generator, which no asset created by the node has.
+    pub fn commitment_full_point(&self) -> Result<ExtendedPoint, IronfishError> {
+        // asset generator | value | owner | sender
+        let mut contents = [0u8; 32 + 8 + PUBLIC_ADDRESS_SIZE + PUBLIC_ADDRESS_SIZE];
+        contents[0..32].copy_from_slice(&AffinePoint::from(asset_generator(&self.asset_id)?).to_bytes());
+        contents[32..40].copy_from_slice(&self.value.to_le_bytes());
+        contents[40..72].copy_from_slice(&self.owner);
+        contents[72..104].copy_from_slice(&self.sender);
+
+        let hash_of_contents = pedersen_hash(&NOTE_COMMITMENT_PERSONALIZATION_BITS, &contents);
+        let randomness_generator = find_group_hash(b"r", PEDERSEN_HASH_GENERATORS_PERSONALIZATION);
+
+        Ok(randomness_generator * self.randomness + hash_of_contents)
+    }
+
+    /// Note commitment as found in output descriptions, the u coordinate of the full point
+    pub fn commitment(&self) -> Result<[u8; NOTE_COMMITMENT_SIZE], IronfishError> {
+        Ok(AffinePoint::from(self.commitment_full_point()?).get_u().to_bytes())
+    }
+
+    /// Nullifier of the note at `position` in the note commitment tree, for the
+    /// account with nullifier deriving key `nk`
+    pub fn nullifier(&self, nk: &AffinePoint, position: u64) -> Result<[u8; NULLIFIER_SIZE], IronfishError> {
+        let position_generator = find_group_hash(b"", NULLIFIER_POSITION_IN_TREE_GENERATOR_PERSONALIZATION);
+        let rho = self.commitment_full_point()? + position_generator * Fr::from(position);
+
+        let mut nullifier = [0u8; NULLIFIER_SIZE];
+        nullifier.copy_from_slice(
+            Blake2s::new()
+                .hash_length(NULLIFIER_SIZE)
+                .personal(PRF_NF_PERSONALIZATION)
+                .to_state()
+                .update(&nk.to_bytes())
+                .update(&AffinePoint::from(rho).to_bytes())
+                .finalize()
+                .as_bytes(),
+        );
+        Ok(nullifier)
+    }
+}
+
+/// Generator of the values of an asset, `BLAKE2s("ironf_cv", asset id)` read as a
+/// point. The cofactor is not cleared, the note commitment hashes this encoding.
+pub fn asset_generator(asset_id: &[u8; 32]) -> Result<ExtendedPoint, IronfishError> {
+    let hash = Blake2s::new()
+        .hash_length(32)
+        .personal(VALUE_COMMITMENT_GENERATOR_PERSONALIZATION)
+        .hash(asset_id);
+
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(hash.as_bytes());
+    let point: AffinePoint =
+        Option::from(AffinePoint::from_bytes(bytes)).ok_or(IronfishError::InvalidAssetIdentifier)?;
+
+    Ok(ExtendedPoint::from(point))
+}
+
+/// Pedersen commitment to `value` of the asset `asset_id`, as found in spend and output
+/// descriptions. The generator of the value is the asset generator with its cofactor
+/// cleared, so that amounts of different assets cannot be mixed.
+pub fn value_commitment(value: u64, asset_id: &[u8; 32], randomness: &Fr) -> Result<ExtendedPoint, IronfishError> {
+    let value_generator = asset_generator(asset_id)?.mul_by_cofactor();
+    if value_generator == ExtendedPoint::identity() {
+        return Err(IronfishError::InvalidAssetIdentifier);
+    }
+
+    let randomness_generator = find_group_hash(b"r", VALUE_COMMITMENT_RANDOMNESS_GENERATOR_PERSONALIZATION);
+
+    Ok(value_generator * Fr::from(value) + randomness_generator * randomness)
+}
+
+/// Point of the prime order subgroup derived from a tag, if the hash is a valid
+/// point whose cofactor can be cleared
+fn group_hash(tag: &[u8], personalization: &[u8; 8]) -> Option<ExtendedPoint> {
+    let hash = Blake2s::new()
+        .hash_length(32)
+        .personal(personalization)
+        .to_state()
+        .update(GH_FIRST_BLOCK)
+        .update(tag)
+        .finalize();
+
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(hash.as_bytes());
+    let point: AffinePoint = Option::from(AffinePoint::from_bytes(bytes))?;
+    let point = ExtendedPoint::from(point).mul_by_cofactor();
+
+    if point == ExtendedPoint::identity() {
+        None
+    } else {
+        Some(point)
+    }
+}
+
+/// First group hash of `tag | i` that is a valid point, for i = 0, 1, ...
+fn find_group_hash(tag: &[u8], personalization: &[u8; 8]) -> ExtendedPoint {
+    let mut data = [0u8; 5];
+    data[..tag.len()].copy_from_slice(tag);
+
+    for i in 0..=u8::MAX {
+        data[tag.len()] = i;
+        if let Some(point) = group_hash(&data[..tag.len() + 1], personalization) {
+            return point;
+        }
+    }
+
+    // A valid point is found after a couple of tries, this is unreachable
+    ExtendedPoint::identity()
+}
+
+/// Pedersen hash of the personalization bits followed by the bits of `data`, least
+/// significant bit of each byte first
+fn pedersen_hash(personalization: &[bool], data: &[u8]) -> ExtendedPoint {
+    let mut bits = personalization
+        .iter()
+        .copied()
+        .chain(data.iter().flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1)));
+
+    let mut result = ExtendedPoint::identity();
+    let mut segment: u32 = 0;
+    loop {
+        let mut acc = Fr::zero();
+        let mut cur = Fr::one();
+        let mut chunks_remaining = PEDERSEN_HASH_CHUNKS_PER_GENERATOR;
+        let mut encountered_bits = false;
+
+        // Each chunk of 3 bits encodes (1 - 2 * c) * (1 + a + 2 * b), shifted by 4 bits
+        // for each chunk already in the segment
+        while let Some(a) = bits.next() {
+            encountered_bits = true;
+            let b = bits.next().unwrap_or(false);
+            let c = bits.next().unwrap_or(false);
+
+            let mut tmp = cur;
+            if a {
+                tmp += cur;
+            }
+            cur = cur.double();
+            if b {
+                tmp += cur;
+            }
+            if c {
+                tmp = -tmp;
+            }
+            acc += tmp;
+
+            chunks_remaining -= 1;
+            if chunks_remaining == 0 {
+                break;
+            }
+            cur = cur.double().double().double();
+        }
+
+        if !encountered_bits {
+            break;
+        }
+
+        let generator = find_group_hash(&segment.to_le_bytes(), PEDERSEN_HASH_GENERATORS_PERSONALIZATION);
+        result += generator * acc;
+        segment += 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ironfish::constants::NATIVE_ASSET;
+    use crate::ironfish::test_vectors::{
+        SPEND_1_OUTPUT_1, SPEND_1_OUTPUT_4_MINT_1_BURN_1, SPEND_2_OUTPUT_6_MINT_2_BURN_1,
+    };
+    use crate::ironfish::transaction::{Transaction, SIGNATURE_SIZE};
+    use blake2b_simd::Params as Blake2b;
+    use ledger_device_sdk::assert_eq_err as assert_eq;
+    use ledger_device_sdk::testing::TestType;
+    use testmacro::test_item as test;
+
+    const OWNER: &str = "673a8bfd38f977ea1e511a40656d2a7a832252bc40c14c2760ad90647d55b2ef";
+    const SENDER: &str = "5ab054e037daa99690d38c0e54762fd65425dabe03ca788ed7f55cd559015024";
+
+    fn test_note(asset_id: [u8; 32]) -> Note {
+        let mut owner = [0u8; PUBLIC_ADDRESS_SIZE];
+        hex::decode_to_slice(OWNER, &mut owner).unwrap();
+        let mut sender = [0u8; PUBLIC_ADDRESS_SIZE];
+        hex::decode_to_slice(SENDER, &mut sender).unwrap();
+
+        Note {
+            owner,
+            asset_id,
+            value: 123_456_789,
+            randomness: Fr::from(42u64),
+            memo: [0u8; MEMO_SIZE],
+            sender,
+        }
+    }
+
+    #[test]
+    fn asset_generator_is_the_hash_of_the_asset_id() {
+        let generator = asset_generator(&NATIVE_ASSET).unwrap();
+        assert_eq!(
+            hex::encode(AffinePoint::from(generator).to_bytes()).as_str(),
+            "13d13a1f6b5b52563d1d3a3765e326dd6441b322f6594f394783e58c80a8a51c"
+        );
+    }
+
+    // No Iron Fish vector is available for these, the values were computed by this
+    // implementation and only guard against regressions
+    #[test]
+    fn note_commitment_regression() {
+        let note = test_note(NATIVE_ASSET);
+        assert_eq!(hex::encode(note.commitment().unwrap()).as_str(), "e69198a76248cbee891a4f51e5c7e60a63645c85c11e313d960da47afa66f914");
+    }
+
+    #[test]
+    fn nullifier_regression() {
+        let note = test_note(NATIVE_ASSET);
+        let nk = AffinePoint::from(find_group_hash(b"", PEDERSEN_HASH_GENERATORS_PERSONALIZATION));
+        assert_eq!(hex::encode(note.nullifier(&nk, 7).unwrap()).as_str(), "ccfcac0423112d674e6856513acf69771d70be3f1ba6afd37bac9bda67f587c3");
+        assert_eq!(note.nullifier(&nk, 7) == note.nullifier(&nk, 8), false);
+    }
+
+    /// Checks the binding signature of a transaction, which only verifies if the value
+    /// commitments of the mints, burns and fee use the generators of the node
+    fn binding_signature_verifies(raw_tx: &str) -> bool {
+        let raw = hex::decode(raw_tx).unwrap();
+        let tx = Transaction::parse(&raw).unwrap();
+        let point = |bytes: &[u8]| {
+            let mut encoded = [0u8; 32];
+            encoded.copy_from_slice(bytes);
+            ExtendedPoint::from(Option::<AffinePoint>::from(AffinePoint::from_bytes(encoded)).unwrap())
+        };
+        let committed = |value: u64, asset_id: &[u8; 32]| value_commitment(value, asset_id, &Fr::zero()).unwrap();
+
+        let mut binding_key = ExtendedPoint::identity();
+        for spend in tx.spends.iter() {
+            binding_key += point(&spend.value_commitment);
+        }
+        for output in tx.outputs.iter() {
+            binding_key -= point(&output.value_commitment);
+        }
+        for mint in tx.mints.iter() {
+            binding_key += committed(mint.value, &mint.asset_id);
+        }
+        for burn in tx.burns.iter() {
+            binding_key -= committed(burn.value, &burn.asset_id);
+        }
+        binding_key -= committed(tx.fee as u64, &NATIVE_ASSET);
+
+        // RedJubjub signature of binding key | signature hash, the last bytes of the transaction
+        let signature = &raw[raw.len() - SIGNATURE_SIZE..];
+        let mut s = [0u8; 32];
+        s.copy_from_slice(&signature[32..]);
+        let s: Fr = Option::from(Fr::from_bytes(&s)).unwrap();
+        let challenge = Blake2b::new()
+            .hash_length(64)
+            .personal(b"Zcash_RedJubjubH")
+            .to_state()
+            .update(&signature[..32])
+            .update(&AffinePoint::from(binding_key).to_bytes())
+            .update(&tx.signature_hash())
+            .finalize();
+        let mut wide = [0u8; 64];
+        wide.copy_from_slice(challenge.as_bytes());
+        let c = Fr::from_bytes_wide(&wide);
+
+        let base = find_group_hash(b"r", VALUE_COMMITMENT_RANDOMNESS_GENERATOR_PERSONALIZATION);
+        (base * s - point(&signature[..32]) - binding_key * c).mul_by_cofactor() == ExtendedPoint::identity()
+    }
+
+    #[test]
+    fn value_commitment_verifies_binding_signatures() {
+        assert_eq!(binding_signature_verifies(SPEND_1_OUTPUT_1), true);
+        assert_eq!(binding_signature_verifies(SPEND_1_OUTPUT_4_MINT_1_BURN_1), true);
+        assert_eq!(binding_signature_verifies(SPEND_2_OUTPUT_6_MINT_2_BURN_1), true);
+    }
+
+    #[test]
+    fn note_commitment_binds_the_asset() {
+        let mut other_asset = NATIVE_ASSET;
+        while asset_generator(&other_asset).is_err() || other_asset == NATIVE_ASSET {
+            other_asset[0] = other_asset[0].wrapping_add(1);
+        }
+
+        assert_eq!(test_note(NATIVE_ASSET).commitment() == test_note(other_asset).commitment(), false);
+    }
+}
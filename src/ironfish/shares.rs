@@ -0,0 +1,244 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Arithmetic on Shamir shares of the multisig spend authorizing key.
+//!
+//! Shares, identifiers and polynomial coefficients are jubjub scalars, and the
+//! public commitments are multiples of the FROST generator, which for Iron Fish
+//! is the spending key generator.
+
+use alloc::vec::Vec;
+use ironfish_frost::frost::Identifier;
+use jubjub::{AffinePoint, ExtendedPoint, Fr};
+use crate::ironfish::constants::SPENDING_KEY_GENERATOR;
+use crate::ironfish::errors::IronfishError;
+
+pub const SCALAR_LEN: usize = 32;
+pub const POINT_LEN: usize = 32;
+
+/// Parse a little endian scalar, as serialized by FROST
+pub fn scalar_from_bytes(bytes: &[u8]) -> Result<Fr, IronfishError> {
+    let array = <[u8; SCALAR_LEN]>::try_from(bytes).map_err(|_| IronfishError::InvalidFr)?;
+    Option::from(Fr::from_bytes(&array)).ok_or(IronfishError::InvalidFr)
+}
+
+/// Scalar a FROST identifier stands for, which is where shares are evaluated
+pub fn identifier_to_scalar(identifier: &Identifier) -> Result<Fr, IronfishError> {
+    scalar_from_bytes(identifier.serialize().as_ref())
+}
+
+/// Parse a point, which must be in the prime order subgroup
+pub fn point_from_bytes(bytes: &[u8]) -> Result<ExtendedPoint, IronfishError> {
+    let array = <[u8; POINT_LEN]>::try_from(bytes).map_err(|_| IronfishError::InvalidData)?;
+    let point: AffinePoint = Option::from(AffinePoint::from_bytes(array)).ok_or(IronfishError::InvalidData)?;
+    let point = ExtendedPoint::from(point);
+
+    if bool::from(point.is_small_order()) || !bool::from(point.is_torsion_free()) {
+        return Err(IronfishError::IsSmallOrder);
+    }
+
+    Ok(point)
+}
+
+pub fn point_to_bytes(point: &ExtendedPoint) -> [u8; POINT_LEN] {
+    AffinePoint::from(point).to_bytes()
+}
+
+/// Multiply the FROST generator by a scalar
+pub fn generator_mul(scalar: &Fr) -> ExtendedPoint {
+    SPENDING_KEY_GENERATOR.multiply_bits(&scalar.to_bytes())
+}
+
+/// Lagrange coefficient of `x` at zero, for the set of participants `set`, which must
+/// contain `x` and no duplicates.
+pub fn lagrange_coefficient(x: &Fr, set: &[Fr]) -> Result<Fr, IronfishError> {
+    lagrange_coefficient_at(x, set, &Fr::zero())
+}
+
+/// Lagrange coefficient of `x` at `at`, for the set of participants `set`, which must
+/// contain `x` and no duplicates.
+pub fn lagrange_coefficient_at(x: &Fr, set: &[Fr], at: &Fr) -> Result<Fr, IronfishError> {
+    if !set.contains(x) {
+        return Err(IronfishError::IllegalValue);
+    }
+
+    let mut numerator = Fr::one();
+    let mut denominator = Fr::one();
+    for x_j in set.iter().filter(|x_j| *x_j != x) {
+        numerator *= at - x_j;
+        denominator *= x - x_j;
+    }
+
+    // Never zero, every factor is non zero since x is filtered out
+    let inverse: Fr = Option::from(denominator.invert()).ok_or(IronfishError::IllegalValue)?;
+    Ok(numerator * inverse)
+}
+
+/// Secret polynomial of a dealer, coefficients in increasing degree
+pub struct Polynomial {
+    coefficients: Vec<Fr>,
+}
+
+impl Polynomial {
+    /// Polynomial with the given constant term and random higher degree
+    /// coefficients, each one derived from 64 bytes of entropy.
+    pub fn new(constant: Fr, entropy: &[[u8; 64]]) -> Self {
+        let mut coefficients = Vec::with_capacity(entropy.len() + 1);
+        coefficients.push(constant);
+        for bytes in entropy.iter() {
+            coefficients.push(Fr::from_bytes_wide(bytes));
+        }
+
+        Polynomial { coefficients }
+    }
+
+    pub fn evaluate(&self, x: &Fr) -> Fr {
+        let mut result = Fr::zero();
+        for coefficient in self.coefficients.iter().rev() {
+            result = result * x + coefficient;
+        }
+        result
+    }
+
+    /// Feldman commitments to the coefficients
+    pub fn commitments(&self) -> Vec<ExtendedPoint> {
+        self.coefficients.iter().map(generator_mul).collect()
+    }
+}
+
+impl Drop for Polynomial {
+    fn drop(&mut self) {
+        for coefficient in self.coefficients.iter_mut() {
+            *coefficient = Fr::zero();
+        }
+    }
+}
+
+/// Evaluate a committed polynomial at `x`, which gives the public counterpart of the
+/// share of the participant with identifier `x`.
+pub fn evaluate_commitments(commitments: &[ExtendedPoint], x: &Fr) -> ExtendedPoint {
+    let mut result = ExtendedPoint::identity();
+    for commitment in commitments.iter().rev() {
+        result = result * x + commitment;
+    }
+    result
+}
+
+/// Check a share received from a dealer against the commitments it published
+pub fn verify_share(share: &Fr, commitments: &[ExtendedPoint], x: &Fr) -> bool {
+    generator_mul(share) == evaluate_commitments(commitments, x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ledger_device_sdk::assert_eq_err as assert_eq;
+    use ledger_device_sdk::testing::TestType;
+    use testmacro::test_item as test;
+
+    fn entropy(seeds: &[u8]) -> Vec<[u8; 64]> {
+        seeds.iter().map(|seed| [*seed; 64]).collect()
+    }
+
+    fn xs(values: &[u64]) -> Vec<Fr> {
+        values.iter().map(|v| Fr::from(*v)).collect()
+    }
+
+    fn interpolate(xs: &[Fr], ys: &[Fr]) -> Fr {
+        let mut result = Fr::zero();
+        for (x, y) in xs.iter().zip(ys.iter()) {
+            result += lagrange_coefficient(x, xs).unwrap() * y;
+        }
+        result
+    }
+
+    #[test]
+    fn lagrange_interpolation_recovers_the_constant() {
+        let secret = Fr::from(1234u64);
+        let polynomial = Polynomial::new(secret, &entropy(&[1, 2]));
+
+        // Any 3 shares of a degree 2 polynomial give the constant term
+        for set in [[1u64, 2, 3], [2, 4, 7], [7, 3, 5]] {
+            let set = xs(&set);
+            let shares: Vec<Fr> = set.iter().map(|x| polynomial.evaluate(x)).collect();
+            assert_eq!(interpolate(&set, &shares), secret);
+        }
+
+        // 2 shares are not enough
+        let set = xs(&[1, 2]);
+        let shares: Vec<Fr> = set.iter().map(|x| polynomial.evaluate(x)).collect();
+        assert_eq!(interpolate(&set, &shares) == secret, false);
+    }
+
+    #[test]
+    fn lagrange_coefficient_needs_x_in_the_set() {
+        assert_eq!(lagrange_coefficient(&Fr::from(4u64), &xs(&[1, 2, 3])).is_err(), true);
+        assert_eq!(lagrange_coefficient(&Fr::from(1u64), &xs(&[1, 2, 3])).is_ok(), true);
+    }
+
+    #[test]
+    fn lagrange_interpolation_at_any_point() {
+        let polynomial = Polynomial::new(Fr::from(77u64), &entropy(&[6, 7]));
+        let set = xs(&[2, 5, 9]);
+        let at = Fr::from(11u64);
+
+        let mut result = Fr::zero();
+        for x in set.iter() {
+            result += lagrange_coefficient_at(x, &set, &at).unwrap() * polynomial.evaluate(x);
+        }
+        assert_eq!(result, polynomial.evaluate(&at));
+    }
+
+    #[test]
+    fn verify_share_against_commitments() {
+        let polynomial = Polynomial::new(Fr::from(99u64), &entropy(&[3, 4]));
+        let commitments = polynomial.commitments();
+        let x = Fr::from(5u64);
+        let share = polynomial.evaluate(&x);
+
+        assert_eq!(commitments[0], generator_mul(&Fr::from(99u64)));
+        assert_eq!(verify_share(&share, &commitments, &x), true);
+        assert_eq!(verify_share(&(share + Fr::one()), &commitments, &x), false);
+        assert_eq!(verify_share(&share, &commitments, &Fr::from(6u64)), false);
+    }
+
+    #[test]
+    fn reshare_keeps_the_account_key() {
+        // 2 of 3 account, reshared by participants 1 and 3 to a 3 of 4 account
+        let secret = Fr::from(424242u64);
+        let old = Polynomial::new(secret, &entropy(&[5]));
+        let dealers = xs(&[1, 3]);
+        let new_set = xs(&[2, 4, 6, 8]);
+
+        let mut new_shares = alloc::vec![Fr::zero(); new_set.len()];
+        let mut group_commitments = alloc::vec![ExtendedPoint::identity(); 3];
+        for (i, x) in dealers.iter().enumerate() {
+            let lambda = lagrange_coefficient(x, &dealers).unwrap();
+            let polynomial = Polynomial::new(lambda * old.evaluate(x), &entropy(&[10 + i as u8, 20 + i as u8]));
+
+            for (share, new_x) in new_shares.iter_mut().zip(new_set.iter()) {
+                *share += polynomial.evaluate(new_x);
+            }
+            for (sum, commitment) in group_commitments.iter_mut().zip(polynomial.commitments().iter()) {
+                *sum += commitment;
+            }
+        }
+
+        assert_eq!(group_commitments[0], generator_mul(&secret));
+        for (share, x) in new_shares.iter().zip(new_set.iter()) {
+            assert_eq!(verify_share(share, &group_commitments, x), true);
+        }
+        assert_eq!(interpolate(&new_set[1..], &new_shares[1..]), secret);
+    }
+
+    #[test]
+    fn point_encoding_round_trip() {
+        let point = generator_mul(&Fr::from(7u64));
+        assert_eq!(point_from_bytes(&point_to_bytes(&point)).unwrap(), point);
+
+        // The identity has small order
+        assert_eq!(point_from_bytes(&point_to_bytes(&ExtendedPoint::identity())).is_err(), true);
+        assert_eq!(scalar_from_bytes(&[0xff; SCALAR_LEN]).is_err(), true);
+    }
+}
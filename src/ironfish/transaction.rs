@@ -0,0 +1,416 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+
+//! Minimal parser for serialized unsigned transactions.
+//!
+//! Only the fields needed to review a transaction and to recompute its
+//! signature hash are extracted. Proofs and encrypted payloads are kept as
+//! slices into the input buffer so that no large copies land on the heap.
+
+use alloc::vec::Vec;
+use blake2b_simd::Params as Blake2b;
+use blake2s_simd::Params as Blake2s;
+use crate::ironfish::constants::{ASSET_ID_PERSONALIZATION, GH_FIRST_BLOCK, SIGNATURE_HASH_PERSONALIZATION, TRANSACTION_SIGNATURE_VERSION};
+use crate::ironfish::errors::IronfishError;
+use crate::ironfish::note::{NOTE_CIPHERTEXT_SIZE, NOTE_ENCRYPTION_MAC_SIZE};
+
+pub const PROOF_SIZE: usize = 192;
+pub const SIGNATURE_SIZE: usize = 64;
+pub const SCALAR_SIZE: usize = 32;
+pub const POINT_SIZE: usize = 32;
+pub const ASSET_ID_LENGTH: usize = 32;
+pub const ASSET_NAME_LENGTH: usize = 32;
+pub const ASSET_METADATA_LENGTH: usize = 96;
+/// Encrypted note plaintext followed by the AEAD MAC
+pub const ENCRYPTED_NOTE_WITH_MAC_SIZE: usize = NOTE_CIPHERTEXT_SIZE + NOTE_ENCRYPTION_MAC_SIZE;
+/// Encrypted note keys plaintext (64 bytes) followed by the AEAD MAC
+pub const NOTE_ENCRYPTION_KEYS_SIZE: usize = 64 + 16;
+
+const TRANSACTION_VERSION_1: u8 = 1;
+const TRANSACTION_VERSION_2: u8 = 2;
+
+pub struct SpendDescription<'a> {
+    pub public_key_randomness: [u8; SCALAR_SIZE],
+    pub value_commitment: [u8; POINT_SIZE],
+    pub root_hash: [u8; SCALAR_SIZE],
+    pub tree_size: u32,
+    pub nullifier: [u8; 32],
+    /// Serialized description, without the public key randomness
+    raw: &'a [u8],
+}
+
+pub struct OutputDescription<'a> {
+    pub value_commitment: [u8; POINT_SIZE],
+    pub note_commitment: [u8; SCALAR_SIZE],
+    pub ephemeral_public_key: [u8; POINT_SIZE],
+    pub encrypted_note: &'a [u8],
+    pub note_encryption_keys: &'a [u8],
+    raw: &'a [u8],
+}
+
+pub struct MintDescription<'a> {
+    pub public_key_randomness: [u8; SCALAR_SIZE],
+    pub asset_id: [u8; ASSET_ID_LENGTH],
+    pub creator: [u8; 32],
+    pub name: &'a [u8],
+    pub metadata: &'a [u8],
+    pub nonce: u8,
+    pub value: u64,
+    pub owner: [u8; 32],
+    pub transfer_ownership_to: Option<[u8; 32]>,
+    /// Serialized description, without the public key randomness
+    raw: &'a [u8],
+}
+
+pub struct BurnDescription<'a> {
+    pub asset_id: [u8; ASSET_ID_LENGTH],
+    pub value: u64,
+    raw: &'a [u8],
+}
+
+/// Unsigned transaction, as produced by the wallet before multisig signing.
+pub struct Transaction<'a> {
+    pub version: u8,
+    pub fee: i64,
+    pub expiration: u32,
+    pub randomized_public_key: [u8; POINT_SIZE],
+    pub public_key_randomness: [u8; SCALAR_SIZE],
+    pub spends: Vec<SpendDescription<'a>>,
+    pub outputs: Vec<OutputDescription<'a>>,
+    pub mints: Vec<MintDescription<'a>>,
+    pub burns: Vec<BurnDescription<'a>>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&'a [u8], IronfishError> {
+        let end = self.pos.checked_add(len).ok_or(IronfishError::InvalidTransaction)?;
+        let slice = self.data.get(self.pos..end).ok_or(IronfishError::InvalidTransaction)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], IronfishError> {
+        let mut result = [0u8; N];
+        result.copy_from_slice(self.read_slice(N)?);
+        Ok(result)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, IronfishError> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, IronfishError> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, IronfishError> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_i64(&mut self) -> Result<i64, IronfishError> {
+        Ok(i64::from_le_bytes(self.read_array()?))
+    }
+
+    fn since(&self, start: usize) -> &'a [u8] {
+        &self.data[start..self.pos]
+    }
+}
+
+impl<'a> Transaction<'a> {
+    /// Parse a serialized unsigned transaction. The whole input must be consumed.
+    pub fn parse(data: &'a [u8]) -> Result<Self, IronfishError> {
+        let mut reader = Reader::new(data);
+
+        let version = reader.read_u8()?;
+        if version != TRANSACTION_VERSION_1 && version != TRANSACTION_VERSION_2 {
+            return Err(IronfishError::InvalidTransactionVersion);
+        }
+
+        let num_spends = reader.read_u64()?;
+        let num_outputs = reader.read_u64()?;
+        let num_mints = reader.read_u64()?;
+        let num_burns = reader.read_u64()?;
+        let fee = reader.read_i64()?;
+        let expiration = reader.read_u32()?;
+        let randomized_public_key = reader.read_array()?;
+        let public_key_randomness = reader.read_array()?;
+
+        // Counts come from the host, so vectors are never pre-allocated from them
+        let mut spends = Vec::new();
+        for _ in 0..num_spends {
+            spends.push(Self::parse_spend(&mut reader)?);
+        }
+
+        let mut outputs = Vec::new();
+        for _ in 0..num_outputs {
+            outputs.push(Self::parse_output(&mut reader)?);
+        }
+
+        let mut mints = Vec::new();
+        for _ in 0..num_mints {
+            mints.push(Self::parse_mint(&mut reader, version)?);
+        }
+
+        let mut burns = Vec::new();
+        for _ in 0..num_burns {
+            burns.push(Self::parse_burn(&mut reader)?);
+        }
+
+        // Binding signature, not covered by the signature hash
+        reader.read_slice(SIGNATURE_SIZE)?;
+
+        if reader.pos != data.len() {
+            return Err(IronfishError::InvalidTransaction);
+        }
+
+        Ok(Transaction {
+            version,
+            fee,
+            expiration,
+            randomized_public_key,
+            public_key_randomness,
+            spends,
+            outputs,
+            mints,
+            burns,
+        })
+    }
+
+    fn parse_spend(reader: &mut Reader<'a>) -> Result<SpendDescription<'a>, IronfishError> {
+        let public_key_randomness = reader.read_array()?;
+
+        let start = reader.pos;
+        reader.read_slice(PROOF_SIZE)?;
+        let value_commitment = reader.read_array()?;
+        let root_hash = reader.read_array()?;
+        let tree_size = reader.read_u32()?;
+        let nullifier = reader.read_array()?;
+        reader.read_slice(SIGNATURE_SIZE)?;
+
+        Ok(SpendDescription {
+            public_key_randomness,
+            value_commitment,
+            root_hash,
+            tree_size,
+            nullifier,
+            raw: reader.since(start),
+        })
+    }
+
+    fn parse_output(reader: &mut Reader<'a>) -> Result<OutputDescription<'a>, IronfishError> {
+        let start = reader.pos;
+        reader.read_slice(PROOF_SIZE)?;
+        let value_commitment = reader.read_array()?;
+        let note_commitment = reader.read_array()?;
+        let ephemeral_public_key = reader.read_array()?;
+        let encrypted_note = reader.read_slice(ENCRYPTED_NOTE_WITH_MAC_SIZE)?;
+        let note_encryption_keys = reader.read_slice(NOTE_ENCRYPTION_KEYS_SIZE)?;
+
+        Ok(OutputDescription {
+            value_commitment,
+            note_commitment,
+            ephemeral_public_key,
+            encrypted_note,
+            note_encryption_keys,
+            raw: reader.since(start),
+        })
+    }
+
+    fn parse_mint(reader: &mut Reader<'a>, version: u8) -> Result<MintDescription<'a>, IronfishError> {
+        let public_key_randomness = reader.read_array()?;
+
+        let start = reader.pos;
+        reader.read_slice(PROOF_SIZE)?;
+        let creator = reader.read_array()?;
+        let name = reader.read_slice(ASSET_NAME_LENGTH)?;
+        let metadata = reader.read_slice(ASSET_METADATA_LENGTH)?;
+        let nonce = reader.read_u8()?;
+        let value = reader.read_u64()?;
+
+        let (owner, transfer_ownership_to) = if version >= TRANSACTION_VERSION_2 {
+            let owner = reader.read_array()?;
+            let transfer_ownership_to = match reader.read_u8()? {
+                0 => None,
+                _ => Some(reader.read_array()?),
+            };
+            (owner, transfer_ownership_to)
+        } else {
+            (creator, None)
+        };
+        reader.read_slice(SIGNATURE_SIZE)?;
+
+        Ok(MintDescription {
+            public_key_randomness,
+            asset_id: asset_identifier(&creator, name, metadata, nonce),
+            creator,
+            name,
+            metadata,
+            nonce,
+            value,
+            owner,
+            transfer_ownership_to,
+            raw: reader.since(start),
+        })
+    }
+
+    fn parse_burn(reader: &mut Reader<'a>) -> Result<BurnDescription<'a>, IronfishError> {
+        let start = reader.pos;
+        let asset_id = reader.read_array()?;
+        let value = reader.read_u64()?;
+
+        Ok(BurnDescription {
+            asset_id,
+            value,
+            raw: reader.since(start),
+        })
+    }
+
+    /// Calculate a hash of the transaction data, the same way the wallet does, so the
+    /// frost message can be checked against the transaction being shown to the user.
+    pub fn signature_hash(&self) -> [u8; 32] {
+        let mut hasher = Blake2b::new()
+            .hash_length(32)
+            .personal(SIGNATURE_HASH_PERSONALIZATION)
+            .to_state();
+
+        hasher.update(TRANSACTION_SIGNATURE_VERSION);
+        hasher.update(&[self.version]);
+        hasher.update(&self.expiration.to_le_bytes());
+        hasher.update(&self.fee.to_le_bytes());
+        hasher.update(&self.randomized_public_key);
+
+        // Spends and mints are hashed without their authorizing signature
+        for spend in self.spends.iter() {
+            hasher.update(&spend.raw[..spend.raw.len() - SIGNATURE_SIZE]);
+        }
+        for output in self.outputs.iter() {
+            hasher.update(output.raw);
+        }
+        for mint in self.mints.iter() {
+            hasher.update(&mint.raw[..mint.raw.len() - SIGNATURE_SIZE]);
+        }
+        for burn in self.burns.iter() {
+            hasher.update(burn.raw);
+        }
+
+        let mut hash_result = [0; 32];
+        hash_result.copy_from_slice(hasher.finalize().as_ref());
+        hash_result
+    }
+}
+
+/// Derive the identifier of a custom asset from its creation parameters.
+pub fn asset_identifier(creator: &[u8; 32], name: &[u8], metadata: &[u8], nonce: u8) -> [u8; ASSET_ID_LENGTH] {
+    let mut asset_id = [0; ASSET_ID_LENGTH];
+    asset_id.copy_from_slice(
+        Blake2s::new()
+            .hash_length(ASSET_ID_LENGTH)
+            .personal(ASSET_ID_PERSONALIZATION)
+            .to_state()
+            .update(GH_FIRST_BLOCK)
+            .update(creator)
+            .update(name)
+            .update(metadata)
+            .update(&[nonce])
+            .finalize()
+            .as_bytes(),
+    );
+    asset_id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ironfish::test_vectors::{
+        SPEND_1_OUTPUT_1, SPEND_1_OUTPUT_4_MINT_1_BURN_1, SPEND_2_OUTPUT_6_MINT_2_BURN_1,
+    };
+    use ledger_device_sdk::assert_eq_err as assert_eq;
+    use ledger_device_sdk::testing::TestType;
+    use testmacro::test_item as test;
+
+    fn counts(tx: &Transaction) -> (usize, usize, usize, usize) {
+        (tx.spends.len(), tx.outputs.len(), tx.mints.len(), tx.burns.len())
+    }
+
+    #[test]
+    fn parse_transactions() {
+        let raw = hex::decode(SPEND_1_OUTPUT_1).unwrap();
+        let tx = Transaction::parse(&raw).unwrap();
+        assert_eq!(tx.version, 2);
+        assert_eq!(counts(&tx), (1, 1, 0, 0));
+        assert_eq!(tx.fee, 10);
+
+        let raw = hex::decode(SPEND_1_OUTPUT_4_MINT_1_BURN_1).unwrap();
+        let tx = Transaction::parse(&raw).unwrap();
+        assert_eq!(counts(&tx), (1, 4, 1, 1));
+        assert_eq!(tx.fee, 1);
+        assert_eq!(tx.mints[0].value, 5);
+        assert_eq!(&tx.mints[0].name[..8], b"Testcoin");
+
+        let raw = hex::decode(SPEND_2_OUTPUT_6_MINT_2_BURN_1).unwrap();
+        let tx = Transaction::parse(&raw).unwrap();
+        assert_eq!(counts(&tx), (2, 6, 2, 1));
+        assert_eq!(tx.fee, 1);
+    }
+
+    #[test]
+    fn spends_use_the_transaction_randomness() {
+        let raw = hex::decode(SPEND_2_OUTPUT_6_MINT_2_BURN_1).unwrap();
+        let tx = Transaction::parse(&raw).unwrap();
+        for spend in tx.spends.iter() {
+            assert_eq!(spend.public_key_randomness, tx.public_key_randomness);
+        }
+    }
+
+    #[test]
+    fn mint_asset_identifier_matches_burn() {
+        // The wallet writes the asset id in burns, mints only carry the asset parameters
+        let raw = hex::decode(SPEND_1_OUTPUT_4_MINT_1_BURN_1).unwrap();
+        let tx = Transaction::parse(&raw).unwrap();
+        assert_eq!(tx.mints[0].asset_id, tx.burns[0].asset_id);
+
+        let raw = hex::decode(SPEND_2_OUTPUT_6_MINT_2_BURN_1).unwrap();
+        let tx = Transaction::parse(&raw).unwrap();
+        assert_eq!(tx.mints[0].asset_id, tx.burns[0].asset_id);
+    }
+
+    #[test]
+    fn signature_hashes() {
+        let raw = hex::decode(SPEND_1_OUTPUT_1).unwrap();
+        let tx = Transaction::parse(&raw).unwrap();
+        assert_eq!(hex::encode(tx.signature_hash()), "70a566d09a8d5815691a193ce8c793c20c72521ca3e6ba4b9c04c752bff7de47");
+
+        let raw = hex::decode(SPEND_1_OUTPUT_4_MINT_1_BURN_1).unwrap();
+        let tx = Transaction::parse(&raw).unwrap();
+        assert_eq!(hex::encode(tx.signature_hash()), "a8f9f5f3f3a7690c467640669e088574a7d78a593c4a9a5f2f40490f29c5ea9c");
+
+        let raw = hex::decode(SPEND_2_OUTPUT_6_MINT_2_BURN_1).unwrap();
+        let tx = Transaction::parse(&raw).unwrap();
+        assert_eq!(hex::encode(tx.signature_hash()), "80eec01d057ec69a0083d06b8d9a519164d34136b666a8ce3166f3f7e8f5537b");
+    }
+
+    #[test]
+    fn parse_rejects_malformed_transactions() {
+        let raw = hex::decode(SPEND_1_OUTPUT_1).unwrap();
+
+        assert_eq!(Transaction::parse(&raw[..raw.len() - 1]).is_err(), true);
+
+        let mut extra = raw.clone();
+        extra.push(0);
+        assert_eq!(Transaction::parse(&extra).is_err(), true);
+
+        let mut version = raw.clone();
+        version[0] = 3;
+        assert_eq!(Transaction::parse(&version).is_err(), true);
+    }
+}
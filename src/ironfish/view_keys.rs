@@ -12,6 +12,8 @@
 //! that you have spent.
 //!
 
+use blake2b_simd::Params as Blake2b;
+use jubjub::{AffinePoint, ExtendedPoint};
 use crate::ironfish::public_address::PublicAddress;
 
 const DIFFIE_HELLMAN_PERSONALIZATION: &[u8; 16] = b"Iron Fish shared";
@@ -29,6 +31,26 @@ impl IncomingViewKey {
     pub fn public_address(&self) -> PublicAddress {
         PublicAddress::from_view_key(self)
     }
+
+    /// Secret shared with the sender of a note, from the ephemeral public key the
+    /// note was encrypted with
+    pub fn shared_secret(&self, ephemeral_public_key: &ExtendedPoint) -> [u8; 32] {
+        let shared_secret = AffinePoint::from(ephemeral_public_key.to_niels().multiply_bits(&self.view_key)).to_bytes();
+        let reference_bytes = AffinePoint::from(ephemeral_public_key).to_bytes();
+
+        let mut hash_result = [0u8; 32];
+        hash_result.copy_from_slice(
+            Blake2b::new()
+                .hash_length(32)
+                .personal(DIFFIE_HELLMAN_PERSONALIZATION)
+                .to_state()
+                .update(&shared_secret)
+                .update(&reference_bytes)
+                .finalize()
+                .as_bytes(),
+        );
+        hash_result
+    }
 }
 /// Contains two keys that are required (along with outgoing view key)
 /// to have full view access to an account.
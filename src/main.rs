@@ -17,10 +17,16 @@
 
 #![no_std]
 #![no_main]
+#![cfg_attr(test, feature(custom_test_frameworks))]
+#![cfg_attr(test, reexport_test_harness_main = "test_main")]
+#![cfg_attr(test, test_runner(ledger_device_sdk::testing::sdk_test_runner))]
 
 mod utils;
 mod app_ui {
     pub mod menu;
+    pub mod sign;
+    pub mod keys;
+    pub mod dkg;
 }
 mod ironfish{
     pub mod sapling;
@@ -29,6 +35,13 @@ mod ironfish{
     pub mod errors;
     pub mod multisig;
     pub mod public_address;
+    pub mod transaction;
+    pub mod assets;
+    pub mod address;
+    pub mod note;
+    pub mod shares;
+    #[cfg(test)]
+    pub mod test_vectors;
 }
 
 mod handlers {
@@ -42,17 +55,38 @@ mod handlers {
     pub mod dkg_nonces;
     pub mod dkg_sign;
     pub mod dkg_get_public_package;
+    pub mod provide_asset_descriptor;
+    pub mod get_settings;
+    pub mod dkg_prove_identity;
+    pub mod dkg_reshare_round_1;
+    pub mod dkg_reshare_round_2;
+    pub mod dkg_reshare_round_3;
+    pub mod dkg_refresh_round_1;
+    pub mod dkg_refresh_round_2;
+    pub mod dkg_refresh_round_3;
+    pub mod dkg_import_account;
+    pub mod dkg_delete_account;
+    pub mod dkg_aggregate;
+    pub mod dkg_sign_batch;
+    pub mod dkg_get_participants;
+    pub mod dkg_get_nullifier;
+    pub mod dkg_decrypt_note;
+    pub mod set_asset_key;
 }
 
 mod nvm {
     pub mod buffer;
     pub mod dkg_keys;
+    pub mod settings;
+    pub mod dkg_secrets;
+    pub mod nonce_log;
+    pub mod asset_key;
 }
 
 mod context;
 pub mod accumulator;
 
-use app_ui::menu::ui_menu_main;
+use app_ui::menu::{ui_menu_main, MenuEvent};
 use handlers::{
     dkg_get_identity::handler_dkg_get_identity,
     dkg_round_1::handler_dkg_round_1,
@@ -63,9 +97,26 @@ use handlers::{
     dkg_commitments::handler_dkg_commitments,
     dkg_nonces::handler_dkg_nonces,
     dkg_sign::handler_dkg_sign,
+    provide_asset_descriptor::handler_provide_asset_descriptor,
+    get_settings::handler_get_settings,
+    dkg_prove_identity::handler_dkg_prove_identity,
+    dkg_reshare_round_1::handler_dkg_reshare_round_1,
+    dkg_reshare_round_2::handler_dkg_reshare_round_2,
+    dkg_reshare_round_3::handler_dkg_reshare_round_3,
+    dkg_refresh_round_1::handler_dkg_refresh_round_1,
+    dkg_refresh_round_2::handler_dkg_refresh_round_2,
+    dkg_refresh_round_3::handler_dkg_refresh_round_3,
+    dkg_import_account::handler_dkg_import_account,
+    dkg_delete_account::{delete_account, handler_dkg_delete_account},
+    dkg_aggregate::handler_dkg_aggregate,
+    dkg_sign_batch::handler_dkg_sign_batch,
+    dkg_get_participants::handler_dkg_get_participants,
+    dkg_get_nullifier::handler_dkg_get_nullifier,
+    dkg_decrypt_note::handler_dkg_decrypt_note,
+    set_asset_key::handler_set_asset_key,
 };
 
-use ledger_device_sdk::io::{ApduHeader, Comm, Event, Reply, StatusWords};
+use ledger_device_sdk::io::{ApduHeader, Comm, Reply, StatusWords};
 #[cfg(feature = "pending_review_screen")]
 #[cfg(not(any(target_os = "stax", target_os = "flex")))]
 use ledger_device_sdk::ui::gadgets::display_pending_review;
@@ -77,6 +128,8 @@ extern crate alloc;
 
 #[cfg(any(target_os = "stax", target_os = "flex"))]
 use ledger_device_sdk::nbgl::{init_comm, NbglReviewStatus, StatusType};
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+use crate::nvm::settings::{Settings, KEY_EXPORT_APPROVAL};
 use crate::context::TxContext;
 use crate::handlers::dkg_get_public_package::handler_dkg_get_public_package;
 
@@ -109,6 +162,21 @@ pub enum AppSW {
     InvalidKeyPackage = 0xB015,
     InvalidPublicPackage = 0xB016,
     InvalidGroupSecretKey = 0xB017,
+    InvalidAssetDescriptor = 0xB018,
+    BlindSigningDisabled = 0xB019,
+    InvalidIdentityPath = 0xB01A,
+    InvalidRound1Packages = 0xB01B,
+    DkgSecretNotFound = 0xB01C,
+    DkgReshareFail = 0xB01D,
+    DkgRefreshFail = 0xB01E,
+    InvalidAccountImport = 0xB01F,
+    AccountNotFound = 0xB020,
+    InvalidSignatureShare = 0xB021,
+    NonceReuse = 0xB022,
+    InvalidNote = 0xB023,
+    SpendNotOwned = 0xB024,
+    NoteNotOwned = 0xB025,
+    OutputCommitmentMismatch = 0xB026,
     WrongApduLength = StatusWords::BadLen as u16,
     Ok = 0x9000,
 }
@@ -125,13 +193,30 @@ pub enum Instruction {
     GetAppName,
     DkgGetIdentity,
     DkgGetPublicPackage,
-    DkgRound1 { chunk: u8 },
-    DkgRound2 { chunk: u8 },
-    DkgRound3 { chunk: u8 },
+    DkgRound1 { chunk: u8, flags: u8 },
+    DkgRound2 { chunk: u8, flags: u8 },
+    DkgRound3 { chunk: u8, flags: u8 },
     DkgCommitments { chunk: u8 },
     DkgSign { chunk: u8 },
     DkgGetKeys { key_type: u8 },
     DkgNonces { chunk: u8 },
+    ProvideAssetDescriptor,
+    GetSettings,
+    DkgProveIdentity { flags: u8 },
+    DkgReshareRound1 { chunk: u8, flags: u8 },
+    DkgReshareRound2 { chunk: u8, flags: u8 },
+    DkgReshareRound3 { chunk: u8 },
+    DkgRefreshRound1 { flags: u8 },
+    DkgRefreshRound2 { chunk: u8, flags: u8 },
+    DkgRefreshRound3 { chunk: u8 },
+    DkgImportAccount { chunk: u8, flags: u8 },
+    DkgDeleteAccount,
+    DkgAggregate { chunk: u8 },
+    DkgSignBatch { chunk: u8 },
+    DkgGetParticipants,
+    DkgGetNullifier,
+    DkgDecryptNote,
+    SetAssetKey,
 }
 
 impl TryFrom<ApduHeader> for Instruction {
@@ -153,19 +238,22 @@ impl TryFrom<ApduHeader> for Instruction {
             (3, 0, 0) => Ok(Instruction::GetVersion),
             (4, 0, 0) => Ok(Instruction::GetAppName),
             (16, 0, 0) => Ok(Instruction::DkgGetIdentity),
-            (17, 0..=2, 0) => {
+            (17, 0..=2, 0..=3) => {
                 Ok(Instruction::DkgRound1 {
-                    chunk: value.p1
+                    chunk: value.p1,
+                    flags: value.p2
                 })
             },
-            (18, 0..=2, 0) => {
+            (18, 0..=2, 0..=3) => {
                 Ok(Instruction::DkgRound2 {
-                    chunk: value.p1
+                    chunk: value.p1,
+                    flags: value.p2
                 })
             },
-            (19, 0..=2, 0) => {
+            (19, 0..=2, 0..=3) => {
                 Ok(Instruction::DkgRound3 {
-                    chunk: value.p1
+                    chunk: value.p1,
+                    flags: value.p2
                 })
             },
             (20, 0..=2, 0) => {
@@ -178,7 +266,7 @@ impl TryFrom<ApduHeader> for Instruction {
                     chunk: value.p1
                 })
             },
-            (22, 0, 0..=2) => Ok(Instruction::DkgGetKeys{
+            (22, 0, 0..=4) => Ok(Instruction::DkgGetKeys{
                 key_type: value.p2
             }),
             (23, 0..=2, 0) => {
@@ -189,8 +277,65 @@ impl TryFrom<ApduHeader> for Instruction {
             (24, 0..=2, 0) => {
                 Ok(Instruction::DkgGetPublicPackage)
             },
+            (25, 0, 0) => Ok(Instruction::ProvideAssetDescriptor),
+            (26, 0, 0) => Ok(Instruction::GetSettings),
+            (27, 0, 0..=1) => Ok(Instruction::DkgProveIdentity {
+                flags: value.p2
+            }),
+            (28, 0..=2, 0..=1) => {
+                Ok(Instruction::DkgReshareRound1 {
+                    chunk: value.p1,
+                    flags: value.p2
+                })
+            },
+            (29, 0..=2, 0..=1) => {
+                Ok(Instruction::DkgReshareRound2 {
+                    chunk: value.p1,
+                    flags: value.p2
+                })
+            },
+            (30, 0..=2, 0) => {
+                Ok(Instruction::DkgReshareRound3 {
+                    chunk: value.p1
+                })
+            },
+            (31, 0, 0..=1) => Ok(Instruction::DkgRefreshRound1 {
+                flags: value.p2
+            }),
+            (32, 0..=2, 0..=1) => {
+                Ok(Instruction::DkgRefreshRound2 {
+                    chunk: value.p1,
+                    flags: value.p2
+                })
+            },
+            (33, 0..=2, 0) => {
+                Ok(Instruction::DkgRefreshRound3 {
+                    chunk: value.p1
+                })
+            },
+            (34, 0..=2, 0..=1) => {
+                Ok(Instruction::DkgImportAccount {
+                    chunk: value.p1,
+                    flags: value.p2
+                })
+            },
+            (35, 0, 0) => Ok(Instruction::DkgDeleteAccount),
+            (36, 0..=2, 0) => {
+                Ok(Instruction::DkgAggregate {
+                    chunk: value.p1
+                })
+            },
+            (37, 0..=2, 0) => {
+                Ok(Instruction::DkgSignBatch {
+                    chunk: value.p1
+                })
+            },
+            (38, 0, 0) => Ok(Instruction::DkgGetParticipants),
+            (39, 0, 0) => Ok(Instruction::DkgGetNullifier),
+            (40, 0, 0) => Ok(Instruction::DkgDecryptNote),
+            (41, 0, 0) => Ok(Instruction::SetAssetKey),
             (3..=4, _, _) => Err(AppSW::WrongP1P2),
-            (17..=22, _, _) => Err(AppSW::WrongP1P2),
+            (17..=41, _, _) => Err(AppSW::WrongP1P2),
             (_, _, _) => Err(AppSW::InsNotSupported),
         }
     }
@@ -198,6 +343,10 @@ impl TryFrom<ApduHeader> for Instruction {
 
 #[no_mangle]
 extern "C" fn sample_main() {
+    // Unit tests run on the device, through speculos
+    #[cfg(test)]
+    test_main();
+
     // Create the communication manager, and configure it to accept only APDU from the 0xe0 class.
     // If any APDU with a wrong class value is received, comm will respond automatically with
     // BadCla status word.
@@ -219,22 +368,30 @@ extern "C" fn sample_main() {
     loop {
         // Wait for either a specific button push to exit the app
         // or an APDU command
-        if let Event::Command(ins) = ui_menu_main(&mut comm) {
-            let result = handle_apdu(&mut comm, &ins, &mut tx_ctx);
-            let _status: AppSW = match result {
-                Ok(()) => {
-                    comm.reply_ok();
-                    AppSW::Ok
-                }
-                Err(sw) => {
-                    tx_ctx.reset();
-                    comm.reply(sw);
-                    sw
-                }
-            };
+        match ui_menu_main(&mut comm) {
+            MenuEvent::Command(ins) => {
+                let result = handle_apdu(&mut comm, &ins, &mut tx_ctx);
+                let _status: AppSW = match result {
+                    Ok(()) => {
+                        comm.reply_ok();
+                        AppSW::Ok
+                    }
+                    Err(sw) => {
+                        tx_ctx.reset();
+                        comm.reply(sw);
+                        sw
+                    }
+                };
 
-            #[cfg(any(target_os = "stax", target_os = "flex"))]
-            show_status_if_needed(&ins, &tx_ctx, &_status);
+                #[cfg(any(target_os = "stax", target_os = "flex"))]
+                show_status_if_needed(&ins, &tx_ctx, &_status);
+            }
+            MenuEvent::DeleteAccount => {
+                let _deleted = delete_account().is_ok();
+
+                #[cfg(any(target_os = "stax", target_os = "flex"))]
+                NbglReviewStatus::new().status_type(StatusType::Operation).show(_deleted);
+            }
         }
     }
 }
@@ -247,13 +404,61 @@ fn handle_apdu(comm: &mut Comm, ins: &Instruction, ctx: &mut TxContext) -> Resul
         }
         Instruction::GetVersion => handler_get_version(comm),
         Instruction::DkgGetIdentity => handler_dkg_get_identity(comm),
-        Instruction::DkgRound1 { chunk } => handler_dkg_round_1(comm, *chunk, ctx),
-        Instruction::DkgRound2 { chunk } => handler_dkg_round_2(comm, *chunk, ctx),
-        Instruction::DkgRound3 { chunk } => handler_dkg_round_3(comm, *chunk, ctx),
+        Instruction::DkgRound1 { chunk, flags } => handler_dkg_round_1(comm, *chunk, *flags, ctx),
+        Instruction::DkgRound2 { chunk, flags } => handler_dkg_round_2(comm, *chunk, *flags, ctx),
+        Instruction::DkgRound3 { chunk, flags } => handler_dkg_round_3(comm, *chunk, *flags, ctx),
         Instruction::DkgCommitments { chunk } => handler_dkg_commitments(comm, *chunk, ctx),
         Instruction::DkgSign { chunk } => handler_dkg_sign(comm, *chunk, ctx),
         Instruction::DkgGetKeys {key_type} => handler_dkg_get_keys(comm, key_type),
         Instruction::DkgNonces { chunk } => handler_dkg_nonces(comm, *chunk, ctx),
+        Instruction::ProvideAssetDescriptor => handler_provide_asset_descriptor(comm, ctx),
         Instruction::DkgGetPublicPackage => handler_dkg_get_public_package(comm),
+        Instruction::GetSettings => handler_get_settings(comm),
+        Instruction::DkgProveIdentity { flags } => handler_dkg_prove_identity(comm, *flags),
+        Instruction::DkgReshareRound1 { chunk, flags } => handler_dkg_reshare_round_1(comm, *chunk, *flags, ctx),
+        Instruction::DkgReshareRound2 { chunk, flags } => handler_dkg_reshare_round_2(comm, *chunk, *flags, ctx),
+        Instruction::DkgReshareRound3 { chunk } => handler_dkg_reshare_round_3(comm, *chunk, ctx),
+        Instruction::DkgRefreshRound1 { flags } => handler_dkg_refresh_round_1(comm, *flags),
+        Instruction::DkgRefreshRound2 { chunk, flags } => handler_dkg_refresh_round_2(comm, *chunk, *flags, ctx),
+        Instruction::DkgRefreshRound3 { chunk } => handler_dkg_refresh_round_3(comm, *chunk, ctx),
+        Instruction::DkgImportAccount { chunk, flags } => handler_dkg_import_account(comm, *chunk, *flags, ctx),
+        Instruction::DkgDeleteAccount => handler_dkg_delete_account(comm),
+        Instruction::DkgAggregate { chunk } => handler_dkg_aggregate(comm, *chunk, ctx),
+        Instruction::DkgSignBatch { chunk } => handler_dkg_sign_batch(comm, *chunk, ctx),
+        Instruction::DkgGetParticipants => handler_dkg_get_participants(comm),
+        Instruction::DkgGetNullifier => handler_dkg_get_nullifier(comm),
+        Instruction::DkgDecryptNote => handler_dkg_decrypt_note(comm),
+        Instruction::SetAssetKey => handler_set_asset_key(comm),
+    }
+}
+
+/// Instructions that went through a review end with a status screen on NBGL devices.
+/// Errors reset the context, so a denial is shown whatever the chunk was.
+#[cfg(any(target_os = "stax", target_os = "flex"))]
+fn show_status_if_needed(ins: &Instruction, tx_ctx: &TxContext, status: &AppSW) {
+    let (show_status, status_type) = match (ins, status) {
+        (Instruction::DkgRound1 { .. }, AppSW::Deny) => (true, StatusType::Operation),
+        (Instruction::DkgRound1 { .. }, AppSW::Ok) if tx_ctx.done => (true, StatusType::Operation),
+        (Instruction::DkgReshareRound1 { .. } | Instruction::DkgReshareRound3 { .. }, AppSW::Deny) => (true, StatusType::Operation),
+        (Instruction::DkgReshareRound1 { .. } | Instruction::DkgReshareRound3 { .. }, AppSW::Ok) if tx_ctx.done => (true, StatusType::Operation),
+        (Instruction::DkgRefreshRound1 { .. }, AppSW::Deny | AppSW::Ok) => (true, StatusType::Operation),
+        (Instruction::DkgRefreshRound3 { .. }, AppSW::Deny) => (true, StatusType::Operation),
+        (Instruction::DkgRefreshRound3 { .. }, AppSW::Ok) if tx_ctx.done => (true, StatusType::Operation),
+        (Instruction::DkgDeleteAccount, AppSW::Deny | AppSW::Ok) => (true, StatusType::Operation),
+        (Instruction::DkgImportAccount { .. }, AppSW::Deny) => (true, StatusType::Operation),
+        (Instruction::DkgImportAccount { .. }, AppSW::Ok) if tx_ctx.done => (true, StatusType::Operation),
+        (Instruction::DkgSign { .. } | Instruction::DkgSignBatch { .. }, AppSW::Deny) => (true, StatusType::Transaction),
+        (Instruction::DkgSign { .. } | Instruction::DkgSignBatch { .. }, AppSW::Ok) if tx_ctx.done => (true, StatusType::Transaction),
+        (Instruction::SetAssetKey, AppSW::Deny | AppSW::Ok) => (true, StatusType::Operation),
+        (Instruction::DkgGetKeys { key_type: 0 }, AppSW::Deny | AppSW::Ok)
+            if Settings.is_enabled(KEY_EXPORT_APPROVAL) => (true, StatusType::Address),
+        (Instruction::DkgGetKeys { key_type: 1..=2 }, AppSW::Deny | AppSW::Ok)
+            if Settings.is_enabled(KEY_EXPORT_APPROVAL) => (true, StatusType::Operation),
+        (_, _) => (false, StatusType::Transaction),
+    };
+
+    if show_status {
+        let success = *status == AppSW::Ok;
+        NbglReviewStatus::new().status_type(status_type).show(success);
     }
 }
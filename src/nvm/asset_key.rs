@@ -0,0 +1,50 @@
+use ledger_device_sdk::nvm::*;
+use ledger_device_sdk::NVMData;
+use crate::AppSW;
+use crate::ironfish::assets::ASSET_KEY_LEN;
+
+/// First byte of an uncompressed secp256k1 key, the area is all zeros until a key
+/// is installed
+const UNCOMPRESSED_KEY_PREFIX: u8 = 0x04;
+
+// This is necessary to store the object in NVM and not in RAM
+#[link_section = ".nvm_data"]
+static mut DATA: NVMData<AtomicStorage<[u8; ASSET_KEY_LEN]>> =
+    NVMData::new(AtomicStorage::new(&[0u8; ASSET_KEY_LEN]));
+
+/// Public key allowed to sign asset descriptors. There is none until the user
+/// installs one, asset descriptors are refused until then.
+#[derive(Clone, Copy)]
+pub struct AssetKey;
+
+impl Default for AssetKey {
+    fn default() -> Self {
+        AssetKey
+    }
+}
+
+impl AssetKey {
+    #[inline(never)]
+    pub fn load(&self) -> Option<[u8; ASSET_KEY_LEN]> {
+        let key = unsafe { *DATA.get_mut().get_ref() };
+        if key[0] != UNCOMPRESSED_KEY_PREFIX {
+            return None;
+        }
+
+        Some(key)
+    }
+
+    /// Replaces the installed key, the key must be an uncompressed one
+    #[inline(never)]
+    pub fn save(&self, key: &[u8; ASSET_KEY_LEN]) -> Result<(), AppSW> {
+        if key[0] != UNCOMPRESSED_KEY_PREFIX {
+            return Err(AppSW::InvalidAssetDescriptor);
+        }
+
+        unsafe {
+            DATA.get_mut().update(key);
+        }
+
+        Ok(())
+    }
+}
@@ -6,6 +6,8 @@ use ironfish_frost::dkg::group_key::{GroupSecretKey, GROUP_SECRET_KEY_LEN};
 use ironfish_frost::participant::{Identity, IDENTITY_LEN};
 use alloc::vec::Vec;
 use crate::AppSW;
+use crate::ironfish::multisig::{derive_account_keys, MultisigAccountKeys};
+use crate::ironfish::public_address::PUBLIC_ADDRESS_SIZE;
 use crate::utils::{zlog_stack};
 
 // This is necessary to store the object in NVM and not in RAM
@@ -154,6 +156,62 @@ impl DkgKeys {
         // TODO check that last pos is not bigger than dkg_keys buffer
     }
 
+    /// Write a whole account, replacing the stored one. The account is built in RAM
+    /// first, so nothing is written when it does not fit.
+    #[inline(never)]
+    pub fn save_account(
+        &self,
+        identities: &[Identity],
+        min_signers: u8,
+        key_package: &KeyPackage,
+        public_key_package: &FrostPublicKeyPackage,
+        group_secret_key: &GroupSecretKey,
+    ) -> Result<(), AppSW> {
+        zlog_stack("start save_account\0");
+
+        let key_package_vec = key_package.serialize().map_err(|_| AppSW::InvalidKeyPackage)?;
+        let public_key_package_vec = public_key_package.serialize().map_err(|_| AppSW::InvalidPublicPackage)?;
+
+        let total_len = DATA_STARTING_POS as usize
+            + 2 + identities.len() * IDENTITY_LEN
+            + 2
+            + 2 + key_package_vec.len()
+            + 2 + group_secret_key.len()
+            + 2 + public_key_package_vec.len();
+        if total_len > DKG_KEYS_MAX_SIZE {
+            return Err(AppSW::BufferOutOfBounds);
+        }
+
+        // Same layout as save_round_1_data followed by save_keys
+        let mut updated_data = [0u8; DKG_KEYS_MAX_SIZE];
+        let mut pos = DATA_STARTING_POS as usize;
+
+        write_u16(&mut updated_data, IDENTITIES_POS, pos);
+        write_u16(&mut updated_data, pos, identities.len() * IDENTITY_LEN);
+        pos += 2;
+        for identity in identities.iter() {
+            updated_data[pos..pos + IDENTITY_LEN].copy_from_slice(identity.serialize().as_slice());
+            pos += IDENTITY_LEN;
+        }
+
+        write_u16(&mut updated_data, MIN_SIGNERS_POS, pos);
+        write_u16(&mut updated_data, pos, min_signers as usize);
+        pos += 2;
+
+        write_u16(&mut updated_data, KEY_PACKAGE_POS, pos);
+        pos = write_with_len(&mut updated_data, pos, key_package_vec.as_slice());
+        write_u16(&mut updated_data, GROUP_KEY_PACKAGE_POS, pos);
+        pos = write_with_len(&mut updated_data, pos, group_secret_key.as_slice());
+        write_u16(&mut updated_data, FROST_PUBLIC_PACKAGE_POS, pos);
+        write_with_len(&mut updated_data, pos, public_key_package_vec.as_slice());
+
+        unsafe {
+            DATA.get_mut().update(&updated_data);
+        }
+
+        Ok(())
+    }
+
     #[inline(never)]
     pub fn load_group_secret_key(&self) -> Result<GroupSecretKey, AppSW>{
         zlog_stack("start load_group_secret_key\0");
@@ -230,4 +288,57 @@ impl DkgKeys {
 
         Ok(identities)
     }
+
+    /// An account is stored once the keys of a ceremony, a reshare or an import
+    /// were saved
+    #[inline(never)]
+    pub fn has_account(&self) -> bool {
+        self.get_u16(FROST_PUBLIC_PACKAGE_POS) != 0 && self.load_frost_public_key_package().is_ok()
+    }
+
+    /// Keys of the stored account, derived from the group secret key and the
+    /// verifying key of the frost public key package
+    #[inline(never)]
+    pub fn load_account_keys(&self) -> Result<MultisigAccountKeys, AppSW> {
+        zlog_stack("start load_account_keys\0");
+
+        let group_secret_key = self.load_group_secret_key()?;
+        let frost_public_key_package = self.load_frost_public_key_package()?;
+
+        let verifying_key_vec = frost_public_key_package.verifying_key().serialize()
+            .map_err(|_| AppSW::InvalidPublicPackage)?;
+        let verifying_key = <&[u8; 32]>::try_from(verifying_key_vec.as_slice())
+            .map_err(|_| AppSW::InvalidPublicPackage)?;
+
+        Ok(derive_account_keys(verifying_key, &group_secret_key))
+    }
+
+    #[inline(never)]
+    pub fn load_public_address(&self) -> Result<[u8; PUBLIC_ADDRESS_SIZE], AppSW>{
+        zlog_stack("start load_public_address\0");
+
+        Ok(self.load_account_keys()?.public_address.public_address())
+    }
+
+    /// Overwrite the whole area with zeros, the device is then left without account
+    #[inline(never)]
+    pub fn clear(&self) {
+        zlog_stack("start clear dkg keys\0");
+
+        let updated_data = [0u8; DKG_KEYS_MAX_SIZE];
+        unsafe {
+            DATA.get_mut().update(&updated_data);
+        }
+    }
+}
+
+fn write_u16(data: &mut [u8], index: usize, value: usize) {
+    data[index] = (value >> 8) as u8;
+    data[index + 1] = (value & 0xff) as u8;
+}
+
+fn write_with_len(data: &mut [u8], index: usize, value: &[u8]) -> usize {
+    write_u16(data, index, value.len());
+    data[index + 2..index + 2 + value.len()].copy_from_slice(value);
+    index + 2 + value.len()
 }
@@ -0,0 +1,265 @@
+use alloc::vec::Vec;
+use ironfish_frost::dkg::group_key::{GroupSecretKey, GROUP_SECRET_KEY_LEN};
+use ironfish_frost::frost::keys::KeyPackage;
+use ironfish_frost::frost::keys::PublicKeyPackage as FrostPublicKeyPackage;
+use ironfish_frost::participant::{Identity, IDENTITY_LEN};
+use ledger_device_sdk::nvm::*;
+use ledger_device_sdk::NVMData;
+use crate::AppSW;
+use crate::utils::{zlog_stack};
+
+/// P2 flag of the DKG rounds, set when the secret packages are kept on the device
+/// between rounds instead of being sent to the host and back
+pub const SECRETS_ON_DEVICE: u8 = 0x02;
+
+// Each round secret package is stored with its length (2 bytes) in its own area
+const MAX_SECRET_PACKAGE_LEN: usize = 1500;
+const ROUND_1_SECRET_POS: usize = 0;
+const ROUND_2_SECRET_POS: usize = 2 + MAX_SECRET_PACKAGE_LEN;
+
+// A staged account has an area of its own, with its length first, so a ceremony
+// started meanwhile does not drop a pending reshare or refresh
+const MAX_STAGED_ACCOUNT_LEN: usize = 3000;
+const STAGED_ACCOUNT_MAX_SIZE: usize = 2 + MAX_STAGED_ACCOUNT_LEN;
+
+// This is necessary to store the object in NVM and not in RAM
+pub const DKG_SECRETS_MAX_SIZE: usize = 2 * (2 + MAX_SECRET_PACKAGE_LEN);
+
+#[link_section = ".nvm_data"]
+static mut DATA: NVMData<AlignedStorage<[u8; DKG_SECRETS_MAX_SIZE]>> =
+    NVMData::new(AlignedStorage::new([0u8; DKG_SECRETS_MAX_SIZE]));
+
+#[link_section = ".nvm_data"]
+static mut STAGED_ACCOUNT: NVMData<AlignedStorage<[u8; STAGED_ACCOUNT_MAX_SIZE]>> =
+    NVMData::new(AlignedStorage::new([0u8; STAGED_ACCOUNT_MAX_SIZE]));
+
+#[derive(Clone, Copy)]
+pub struct DkgSecrets;
+
+/// Account computed during a reshare or a refresh, kept aside until every participant
+/// confirmed they got the same public key package. Also the plaintext of an account
+/// import.
+pub struct StagedAccount {
+    pub identities: Vec<Identity>,
+    pub min_signers: u8,
+    pub key_package: KeyPackage,
+    pub public_key_package: FrostPublicKeyPackage,
+    pub group_secret_key: GroupSecretKey,
+}
+
+impl StagedAccount {
+    /// Layout: `min_signers | n | identities | key package | public key package | gsk`,
+    /// packages prefixed by their length.
+    pub fn serialize(&self) -> Result<Vec<u8>, AppSW> {
+        let key_package_vec = self.key_package.serialize().map_err(|_| AppSW::InvalidKeyPackage)?;
+        let public_key_package_vec = self.public_key_package.serialize().map_err(|_| AppSW::InvalidPublicPackage)?;
+
+        let elements = u8::try_from(self.identities.len()).map_err(|_| AppSW::BufferOutOfBounds)?;
+
+        let mut data: Vec<u8> = Vec::new();
+        data.push(self.min_signers);
+        data.push(elements);
+        for identity in self.identities.iter() {
+            data.extend_from_slice(identity.serialize().as_slice());
+        }
+        data.extend_from_slice(&(key_package_vec.len() as u16).to_be_bytes());
+        data.extend_from_slice(&key_package_vec);
+        data.extend_from_slice(&(public_key_package_vec.len() as u16).to_be_bytes());
+        data.extend_from_slice(&public_key_package_vec);
+        data.extend_from_slice(&self.group_secret_key);
+
+        Ok(data)
+    }
+
+    pub fn deserialize(data: &[u8]) -> Result<StagedAccount, AppSW> {
+        let min_signers = *data.first().ok_or(AppSW::InvalidPayload)?;
+        let elements = *data.get(1).ok_or(AppSW::InvalidPayload)? as usize;
+        let mut pos = 2;
+
+        let mut identities = Vec::with_capacity(elements);
+        for _i in 0..elements {
+            let identity = data.get(pos..pos + IDENTITY_LEN).ok_or(AppSW::InvalidPayload)?;
+            identities.push(Identity::deserialize_from(identity).map_err(|_| AppSW::InvalidIdentity)?);
+            pos += IDENTITY_LEN;
+        }
+
+        let (key_package, pos) = read_with_len(data, pos)?;
+        let key_package = KeyPackage::deserialize(key_package).map_err(|_| AppSW::InvalidKeyPackage)?;
+
+        let (public_key_package, pos) = read_with_len(data, pos)?;
+        let public_key_package = FrostPublicKeyPackage::deserialize(public_key_package).map_err(|_| AppSW::InvalidPublicPackage)?;
+
+        let group_secret_key = data.get(pos..pos + GROUP_SECRET_KEY_LEN).ok_or(AppSW::InvalidGroupSecretKey)?;
+        let group_secret_key = *<&GroupSecretKey>::try_from(group_secret_key).map_err(|_| AppSW::InvalidGroupSecretKey)?;
+
+        if pos + GROUP_SECRET_KEY_LEN != data.len() {
+            return Err(AppSW::InvalidPayload);
+        }
+
+        Ok(StagedAccount {
+            identities,
+            min_signers,
+            key_package,
+            public_key_package,
+            group_secret_key,
+        })
+    }
+}
+
+impl Default for DkgSecrets {
+    fn default() -> Self {
+        DkgSecrets
+    }
+}
+
+impl DkgSecrets {
+    #[inline(never)]
+    #[allow(unused)]
+    pub fn get_mut_ref(&mut self) -> &mut AlignedStorage<[u8; DKG_SECRETS_MAX_SIZE]> {
+        unsafe { DATA.get_mut() }
+    }
+
+    #[inline(never)]
+    fn get_u16(&self, start_pos: usize) -> usize {
+        let buffer = unsafe { DATA.get_mut() };
+        let buffer_ref = buffer.get_ref();
+        ((buffer_ref[start_pos] as u16) << 8 | buffer_ref[start_pos+1] as u16) as usize
+    }
+
+    #[inline(never)]
+    fn load_secret(&self, start: usize) -> Result<&[u8], AppSW> {
+        let len = self.get_u16(start);
+        if len == 0 || len > MAX_SECRET_PACKAGE_LEN {
+            return Err(AppSW::DkgSecretNotFound);
+        }
+
+        let buffer = unsafe { DATA.get_mut() };
+        Ok(&buffer.get_ref()[start + 2..start + 2 + len])
+    }
+
+    /// Starting a new ceremony drops the round 2 secret of the previous one
+    #[inline(never)]
+    pub fn save_round_1_secret(&self, secret_package: &[u8]) -> Result<(), AppSW> {
+        zlog_stack("start save_round_1_secret\0");
+
+        if secret_package.len() > MAX_SECRET_PACKAGE_LEN {
+            return Err(AppSW::BufferOutOfBounds);
+        }
+
+        let mut updated_data = [0u8; DKG_SECRETS_MAX_SIZE];
+        let len = secret_package.len();
+        updated_data[ROUND_1_SECRET_POS] = (len >> 8) as u8;
+        updated_data[ROUND_1_SECRET_POS + 1] = (len & 0xff) as u8;
+        updated_data[ROUND_1_SECRET_POS + 2..ROUND_1_SECRET_POS + 2 + len].copy_from_slice(secret_package);
+        unsafe {
+            DATA.get_mut().update(&updated_data);
+        }
+
+        Ok(())
+    }
+
+    #[inline(never)]
+    pub fn save_round_2_secret(&self, secret_package: &[u8]) -> Result<(), AppSW> {
+        zlog_stack("start save_round_2_secret\0");
+
+        if secret_package.len() > MAX_SECRET_PACKAGE_LEN {
+            return Err(AppSW::BufferOutOfBounds);
+        }
+
+        let mut updated_data: [u8; DKG_SECRETS_MAX_SIZE] = unsafe { *DATA.get_mut().get_ref() };
+        let len = secret_package.len();
+        updated_data[ROUND_2_SECRET_POS..].fill(0);
+        updated_data[ROUND_2_SECRET_POS] = (len >> 8) as u8;
+        updated_data[ROUND_2_SECRET_POS + 1] = (len & 0xff) as u8;
+        updated_data[ROUND_2_SECRET_POS + 2..ROUND_2_SECRET_POS + 2 + len].copy_from_slice(secret_package);
+        unsafe {
+            DATA.get_mut().update(&updated_data);
+        }
+
+        Ok(())
+    }
+
+    #[inline(never)]
+    pub fn load_round_1_secret(&self) -> Result<&[u8], AppSW> {
+        zlog_stack("start load_round_1_secret\0");
+        self.load_secret(ROUND_1_SECRET_POS)
+    }
+
+    #[inline(never)]
+    pub fn load_round_2_secret(&self) -> Result<&[u8], AppSW> {
+        zlog_stack("start load_round_2_secret\0");
+        self.load_secret(ROUND_2_SECRET_POS)
+    }
+
+    #[inline(never)]
+    pub fn save_staged_account(&self, account: &StagedAccount) -> Result<(), AppSW> {
+        zlog_stack("start save_staged_account\0");
+
+        let data = account.serialize()?;
+        if data.len() > MAX_STAGED_ACCOUNT_LEN {
+            return Err(AppSW::BufferOutOfBounds);
+        }
+
+        let mut updated_data = [0u8; STAGED_ACCOUNT_MAX_SIZE];
+        let len = data.len();
+        updated_data[0] = (len >> 8) as u8;
+        updated_data[1] = (len & 0xff) as u8;
+        updated_data[2..2 + len].copy_from_slice(&data);
+        unsafe {
+            STAGED_ACCOUNT.get_mut().update(&updated_data);
+        }
+
+        Ok(())
+    }
+
+    #[inline(never)]
+    pub fn load_staged_account(&self) -> Result<StagedAccount, AppSW> {
+        zlog_stack("start load_staged_account\0");
+
+        let buffer = unsafe { STAGED_ACCOUNT.get_mut() };
+        let buffer_ref = buffer.get_ref();
+        let len = ((buffer_ref[0] as usize) << 8) | buffer_ref[1] as usize;
+        if len == 0 || len > MAX_STAGED_ACCOUNT_LEN {
+            return Err(AppSW::DkgSecretNotFound);
+        }
+        let data = &buffer_ref[2..2 + len];
+
+        StagedAccount::deserialize(data)
+    }
+
+    /// Round secrets are only needed until the ceremony ends
+    #[inline(never)]
+    pub fn clear_round_secrets(&self) {
+        zlog_stack("start clear_round_secrets\0");
+
+        let updated_data = [0u8; DKG_SECRETS_MAX_SIZE];
+        unsafe {
+            DATA.get_mut().update(&updated_data);
+        }
+    }
+
+    /// The staged account is only needed until it replaces the stored one
+    #[inline(never)]
+    pub fn clear_staged_account(&self) {
+        zlog_stack("start clear_staged_account\0");
+
+        let updated_data = [0u8; STAGED_ACCOUNT_MAX_SIZE];
+        unsafe {
+            STAGED_ACCOUNT.get_mut().update(&updated_data);
+        }
+    }
+
+    /// Drops whatever a ceremony in progress kept on the device
+    #[inline(never)]
+    pub fn clear(&self) {
+        self.clear_round_secrets();
+        self.clear_staged_account();
+    }
+}
+
+fn read_with_len(data: &[u8], pos: usize) -> Result<(&[u8], usize), AppSW> {
+    let len_bytes = data.get(pos..pos + 2).ok_or(AppSW::InvalidPayload)?;
+    let len = ((len_bytes[0] as usize) << 8) | len_bytes[1] as usize;
+    let value = data.get(pos + 2..pos + 2 + len).ok_or(AppSW::InvalidPayload)?;
+    Ok((value, pos + 2 + len))
+}
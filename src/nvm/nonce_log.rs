@@ -0,0 +1,144 @@
+use blake2b_simd::Params as Blake2b;
+use ledger_device_sdk::nvm::*;
+use ledger_device_sdk::NVMData;
+use crate::AppSW;
+use crate::utils::{zlog_stack};
+
+pub const NONCE_LOG_DIGEST_LEN: usize = 32;
+const ENTRY_LEN: usize = 2 * NONCE_LOG_DIGEST_LEN;
+const NONCE_LOG_ENTRIES: usize = 16;
+
+// Position of the next entry to overwrite, then the entries
+const NEXT_ENTRY_POS: usize = 0;
+const ENTRIES_POS: usize = 1;
+
+const NONCE_COMMITMENTS_PERSONALIZATION: &[u8; 16] = b"IFNonceLogCommit";
+const SIGNED_DATA_PERSONALIZATION: &[u8; 16] = b"IFNonceLogSigned";
+
+// This is necessary to store the object in NVM and not in RAM
+pub const NONCE_LOG_SIZE: usize = ENTRIES_POS + NONCE_LOG_ENTRIES * ENTRY_LEN;
+
+#[link_section = ".nvm_data"]
+static mut DATA: NVMData<AlignedStorage<[u8; NONCE_LOG_SIZE]>> =
+    NVMData::new(AlignedStorage::new([0u8; NONCE_LOG_SIZE]));
+
+/// Ring buffer of the last signatures, each one recorded as a digest of our nonce
+/// commitments and a digest of what was signed with them. The commitments identify the
+/// nonces whatever the host claims they were derived from, and signing something else
+/// with the same nonces would leak the signing share. The log outlives the account, a
+/// share imported again must not be able to reuse nonces it already signed with.
+#[derive(Clone, Copy)]
+pub struct NonceLog;
+
+impl Default for NonceLog {
+    fn default() -> Self {
+        NonceLog
+    }
+}
+
+impl NonceLog {
+    #[inline(never)]
+    #[allow(unused)]
+    pub fn get_mut_ref(&mut self) -> &mut AlignedStorage<[u8; NONCE_LOG_SIZE]> {
+        unsafe { DATA.get_mut() }
+    }
+
+    /// Fails if the nonces were already used to sign something else, otherwise records
+    /// them with the signed data. Signing the same data again is allowed, as it gives
+    /// the same signature share.
+    #[inline(never)]
+    pub fn check_and_record(
+        &self,
+        nonce_commitments: &[u8; NONCE_LOG_DIGEST_LEN],
+        signed_data: &[u8; NONCE_LOG_DIGEST_LEN],
+    ) -> Result<(), AppSW> {
+        zlog_stack("start check_and_record nonces\0");
+
+        let buffer = unsafe { DATA.get_mut() };
+        let data = buffer.get_ref();
+
+        for i in 0..NONCE_LOG_ENTRIES {
+            let start = ENTRIES_POS + i * ENTRY_LEN;
+            if &data[start..start + NONCE_LOG_DIGEST_LEN] == nonce_commitments {
+                if &data[start + NONCE_LOG_DIGEST_LEN..start + ENTRY_LEN] == signed_data {
+                    return Ok(());
+                }
+                return Err(AppSW::NonceReuse);
+            }
+        }
+
+        let mut updated_data = *data;
+        let next = updated_data[NEXT_ENTRY_POS] as usize % NONCE_LOG_ENTRIES;
+        let start = ENTRIES_POS + next * ENTRY_LEN;
+        updated_data[start..start + NONCE_LOG_DIGEST_LEN].copy_from_slice(nonce_commitments);
+        updated_data[start + NONCE_LOG_DIGEST_LEN..start + ENTRY_LEN].copy_from_slice(signed_data);
+        updated_data[NEXT_ENTRY_POS] = ((next + 1) % NONCE_LOG_ENTRIES) as u8;
+        buffer.update(&updated_data);
+
+        Ok(())
+    }
+
+}
+
+fn digest(personalization: &[u8; 16], parts: &[&[u8]]) -> [u8; NONCE_LOG_DIGEST_LEN] {
+    let mut hasher = Blake2b::new()
+        .hash_length(NONCE_LOG_DIGEST_LEN)
+        .personal(personalization)
+        .to_state();
+    for part in parts.iter() {
+        hasher.update(&(part.len() as u16).to_be_bytes());
+        hasher.update(part);
+    }
+
+    let mut result = [0u8; NONCE_LOG_DIGEST_LEN];
+    result.copy_from_slice(hasher.finalize().as_bytes());
+    result
+}
+
+/// Digest of our own nonce commitments, which only depend on the nonces
+pub fn nonce_commitments_digest(commitments: &[u8]) -> [u8; NONCE_LOG_DIGEST_LEN] {
+    digest(NONCE_COMMITMENTS_PERSONALIZATION, &[commitments])
+}
+
+/// Digest of everything the nonces are used to sign
+pub fn signed_data_digest(signing_package: &[u8], randomizer: &[u8]) -> [u8; NONCE_LOG_DIGEST_LEN] {
+    digest(SIGNED_DATA_PERSONALIZATION, &[signing_package, randomizer])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ledger_device_sdk::assert_eq_err as assert_eq;
+    use ledger_device_sdk::testing::TestType;
+    use testmacro::test_item as test;
+
+    #[test]
+    fn nonce_log_rejects_nonces_signing_other_data() {
+        let nonces = nonce_commitments_digest(b"nonce log test commitments");
+        let signed = signed_data_digest(b"signing package", b"randomizer");
+        let other = signed_data_digest(b"signing package", b"other randomizer");
+
+        assert_eq!(NonceLog.check_and_record(&nonces, &signed).is_ok(), true);
+        // Same nonces and data give the same share, the host may ask again
+        assert_eq!(NonceLog.check_and_record(&nonces, &signed).is_ok(), true);
+        assert_eq!(NonceLog.check_and_record(&nonces, &other) == Err(AppSW::NonceReuse), true);
+    }
+
+    #[test]
+    fn nonce_log_accepts_other_nonces() {
+        let signed = signed_data_digest(b"signing package", b"randomizer");
+        let first = nonce_commitments_digest(b"nonce log first commitments");
+        let second = nonce_commitments_digest(b"nonce log second commitments");
+
+        assert_eq!(NonceLog.check_and_record(&first, &signed).is_ok(), true);
+        assert_eq!(NonceLog.check_and_record(&second, &signed).is_ok(), true);
+    }
+
+    #[test]
+    fn nonce_log_digests_are_length_prefixed() {
+        assert_eq!(
+            signed_data_digest(b"ab", b"c") == signed_data_digest(b"a", b"bc"),
+            false
+        );
+    }
+}
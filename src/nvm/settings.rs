@@ -0,0 +1,74 @@
+use ledger_device_sdk::nvm::*;
+use ledger_device_sdk::NVMData;
+
+// This is necessary to store the object in NVM and not in RAM
+pub const SETTINGS_SIZE: usize = 10;
+
+// Position of each setting, disabled (0) by default
+pub const BLIND_SIGNING: usize = 0;
+// Show randomizers, commitments and raw identities on review screens
+pub const EXPERT_MODE: usize = 1;
+// One of the ADDRESS_FORMAT_* values below
+pub const ADDRESS_FORMAT: usize = 2;
+// Ask the user before the address, view or proof keys leave the device
+pub const KEY_EXPORT_APPROVAL: usize = 3;
+
+// Number of settings in use, returned to the host in index order
+pub const NUM_SETTINGS: usize = 4;
+
+pub const ADDRESS_FORMAT_FULL: u8 = 0;
+pub const ADDRESS_FORMAT_SHORT: u8 = 1;
+
+#[link_section = ".nvm_data"]
+static mut DATA: NVMData<AtomicStorage<[u8; SETTINGS_SIZE]>> =
+    NVMData::new(AtomicStorage::new(&[0u8; SETTINGS_SIZE]));
+
+#[derive(Clone, Copy)]
+pub struct Settings;
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings
+    }
+}
+
+impl Settings {
+    #[inline(never)]
+    #[allow(unused)]
+    pub fn get_mut(&mut self) -> &mut AtomicStorage<[u8; SETTINGS_SIZE]> {
+        unsafe { DATA.get_mut() }
+    }
+
+    #[inline(never)]
+    pub fn get_element(&self, index: usize) -> u8 {
+        let settings = unsafe { DATA.get_mut() };
+        settings.get_ref()[index]
+    }
+
+    #[inline(never)]
+    pub fn set_element(&self, index: usize, value: u8) {
+        let settings = unsafe { DATA.get_mut() };
+        let mut updated_data = *settings.get_ref();
+        updated_data[index] = value;
+        settings.update(&updated_data);
+    }
+
+    #[inline(never)]
+    pub fn is_enabled(&self, index: usize) -> bool {
+        self.get_element(index) != 0
+    }
+
+    #[inline(never)]
+    pub fn get_all(&self) -> [u8; NUM_SETTINGS] {
+        let settings = unsafe { DATA.get_mut() };
+        let mut values = [0u8; NUM_SETTINGS];
+        values.copy_from_slice(&settings.get_ref()[..NUM_SETTINGS]);
+        values
+    }
+
+    #[inline(never)]
+    pub fn toggle(&self, index: usize) {
+        let value = if self.is_enabled(index) { 0 } else { 1 };
+        self.set_element(index, value);
+    }
+}
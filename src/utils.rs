@@ -12,6 +12,12 @@ impl AsRef<[u32]> for Bip32Path {
     }
 }
 
+impl From<Vec<u32>> for Bip32Path {
+    fn from(components: Vec<u32>) -> Self {
+        Bip32Path(components)
+    }
+}
+
 impl TryFrom<&[u8]> for Bip32Path {
     type Error = AppSW;
 
@@ -0,0 +1,343 @@
+/** ******************************************************************************
+ *  (c) 2018 - 2024 Zondax AG
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ ******************************************************************************* */
+
+import Zemu from '@zondax/zemu'
+import {defaultOptions, models, PATH} from './common'
+import IronfishApp from '@zondax/ledger-ironfish'
+import {multisig, UnsignedTransaction} from '@ironfish/rust-nodejs'
+import {
+    accountAddress, accountTransaction, approveReview, createAccount, dkgSignTransaction, enableBlindSigning,
+    frostPublicKeyPackage, INS, list, rejectReview, sendApdu, sendChunks, u16,
+} from "./utils";
+
+jest.setTimeout(4500000)
+
+// Lifecycle of an account once the DKG is done, every participant running in its
+// own simulator. Payloads are built here, the js client does not know these
+// instructions.
+describe.each(models)('Account', function (m) {
+    const startSims = async (count: number) => {
+        const sims: Zemu[] = []
+        for (let i = 0; i < count; i++) {
+            const sim = new Zemu(m.path)
+            await sim.start({ ...defaultOptions, model: m.name })
+            sims.push(sim)
+        }
+        return sims
+    }
+
+    const closeSims = async (sims: Zemu[]) => {
+        for (const sim of sims) await sim.close()
+    }
+
+    const apps = (sims: Zemu[]) => (i: number) => new IronfishApp(sims[i].getTransport())
+
+    // randomizer | signing package | n | (identity | signature share) * n
+    const aggregatePayload = (randomizer: Buffer, signingPackage: Buffer, identities: Buffer[], shares: Buffer[]) =>
+        Buffer.concat([
+            u16(randomizer), u16(signingPackage),
+            list(identities.map((identity, i) => Buffer.concat([identity, u16(shares[i])]))),
+        ])
+
+    test(`${m.name} - reshare to a new signer set`, async function () {
+        const sims = await startSims(4)
+        try {
+            const app = apps(sims)
+            // Participants 0, 1 and 2 hold a 2 of 3 account, 2 is replaced by 3
+            const { identities, address } = await createAccount(app, sims.slice(0, 3), 2)
+            const joining = await app(3).dkgGetIdentity(3)
+            if (!joining.identity) throw new Error("no identity found")
+
+            const oldSet = identities.map(i => Buffer.from(i, 'hex'))
+            const newSet = [oldSet[0], oldSet[1], joining.identity]
+            const newSims = [0, 1, 3]
+            const minSigners = 2
+
+            const publicPackage = await app(0).dkgGetPublicPackage()
+            if (!publicPackage.publicPackage) throw new Error("no public package found")
+            const frostPackage = frostPublicKeyPackage(publicPackage.publicPackage)
+
+            // Every current participant deals, the leaving one also deletes its share
+            const dealerPackages: Buffer[] = []
+            for (let i = 0; i < 3; i++) {
+                const request = sendChunks(sims[i], INS.DKG_RESHARE_ROUND_1, 0, Buffer.concat([
+                    Buffer.from([i]), list(newSet), Buffer.from([minSigners]), list(oldSet),
+                ]))
+                await approveReview(sims[i], "Approve")
+                if (i == 2) await approveReview(sims[i], "Approve")
+
+                const round1 = await request
+                expect(round1.returnCode.toString(16)).toEqual("9000")
+                dealerPackages.push(round1.data)
+            }
+
+            const participants = await sendApdu(sims[2], INS.DKG_GET_PARTICIPANTS, 0)
+            expect(participants.returnCode.toString(16)).not.toEqual("9000")
+
+            // The joining participant has no account, so it reviews the address first
+            const confirmations: Buffer[] = []
+            for (const i of newSims) {
+                const request = sendChunks(sims[i], INS.DKG_RESHARE_ROUND_2, 0, Buffer.concat([
+                    Buffer.from([i]), list(newSet), Buffer.from([minSigners]), u16(frostPackage),
+                    list(dealerPackages.map(u16)),
+                ]))
+                if (i == 3) await approveReview(sims[i], "Approve")
+
+                const round2 = await request
+                expect(round2.returnCode.toString(16)).toEqual("9000")
+                confirmations.push(round2.data)
+            }
+
+            for (const i of newSims) {
+                const request = sendChunks(sims[i], INS.DKG_RESHARE_ROUND_3, 0, list(confirmations))
+                await approveReview(sims[i], "Approve")
+
+                const round3 = await request
+                expect(round3.returnCode.toString(16)).toEqual("9000")
+                expect(await accountAddress(app(i))).toEqual(address)
+            }
+        } finally {
+            await closeSims(sims)
+        }
+    })
+
+    test(`${m.name} - refresh the shares`, async function () {
+        const sims = await startSims(3)
+        try {
+            const app = apps(sims)
+            const { address } = await createAccount(app, sims, 2)
+
+            const before = await app(0).dkgGetPublicPackage()
+            if (!before.publicPackage) throw new Error("no public package found")
+
+            const dealerPackages: Buffer[] = []
+            for (let i = 0; i < sims.length; i++) {
+                const request = sendApdu(sims[i], INS.DKG_REFRESH_ROUND_1, 0, Buffer.from([i]))
+                await approveReview(sims[i], "Approve")
+
+                const round1 = await request
+                expect(round1.returnCode.toString(16)).toEqual("9000")
+                dealerPackages.push(round1.data)
+            }
+
+            const confirmations: Buffer[] = []
+            for (let i = 0; i < sims.length; i++) {
+                const round2 = await sendChunks(sims[i], INS.DKG_REFRESH_ROUND_2, 0, Buffer.concat([
+                    Buffer.from([i]), list(dealerPackages.map(u16)),
+                ]))
+                expect(round2.returnCode.toString(16)).toEqual("9000")
+                confirmations.push(round2.data)
+            }
+
+            // A confirmation that is not from the right participant is refused
+            const swapped = await sendChunks(sims[0], INS.DKG_REFRESH_ROUND_3, 0,
+                list([confirmations[1], confirmations[0], confirmations[2]]))
+            expect(swapped.returnCode.toString(16)).not.toEqual("9000")
+
+            for (let i = 0; i < sims.length; i++) {
+                const request = sendChunks(sims[i], INS.DKG_REFRESH_ROUND_3, 0, list(confirmations))
+                await approveReview(sims[i], "Approve")
+
+                const round3 = await request
+                expect(round3.returnCode.toString(16)).toEqual("9000")
+                expect(await accountAddress(app(i))).toEqual(address)
+            }
+
+            // Same account, but every verifying share changed
+            const after = await app(0).dkgGetPublicPackage()
+            if (!after.publicPackage) throw new Error("no public package found")
+            expect(after.publicPackage.toString('hex')).not.toEqual(before.publicPackage.toString('hex'))
+        } finally {
+            await closeSims(sims)
+        }
+    })
+
+    // The host tools have no trusted dealer handing out the group secret key, so only
+    // the rejection of an account that can not be decrypted is covered here
+    test(`${m.name} - import an account not encrypted to the device`, async function () {
+        const sims = await startSims(1)
+        try {
+            const encryptedAccount = Buffer.alloc(200, 0xab)
+            const imported = await sendChunks(sims[0], INS.DKG_IMPORT_ACCOUNT, 0, Buffer.concat([
+                Buffer.from([0]), u16(encryptedAccount),
+            ]))
+            expect(imported.returnCode.toString(16)).toEqual("b01f")
+
+            const participants = await sendApdu(sims[0], INS.DKG_GET_PARTICIPANTS, 0)
+            expect(participants.returnCode.toString(16)).not.toEqual("9000")
+        } finally {
+            await closeSims(sims)
+        }
+    })
+
+    test(`${m.name} - delete the account`, async function () {
+        const sims = await startSims(2)
+        try {
+            const app = apps(sims)
+            await createAccount(app, sims, 2)
+
+            // Rejected, the account stays on the device
+            let request = sendApdu(sims[0], INS.DKG_DELETE_ACCOUNT, 0)
+            await rejectReview(sims[0])
+            expect((await request).returnCode.toString(16)).toEqual("6985")
+
+            let participants = await sendApdu(sims[0], INS.DKG_GET_PARTICIPANTS, 0)
+            expect(participants.returnCode.toString(16)).toEqual("9000")
+
+            request = sendApdu(sims[0], INS.DKG_DELETE_ACCOUNT, 0)
+            await approveReview(sims[0], "Approve")
+            expect((await request).returnCode.toString(16)).toEqual("9000")
+
+            participants = await sendApdu(sims[0], INS.DKG_GET_PARTICIPANTS, 0)
+            expect(participants.returnCode.toString(16)).not.toEqual("9000")
+
+            // Nothing left to delete
+            const deleted = await sendApdu(sims[0], INS.DKG_DELETE_ACCOUNT, 0)
+            expect(deleted.returnCode.toString(16)).toEqual("b020")
+
+            // The other participant still holds its share
+            participants = await sendApdu(sims[1], INS.DKG_GET_PARTICIPANTS, 0)
+            expect(participants.returnCode.toString(16)).toEqual("9000")
+        } finally {
+            await closeSims(sims)
+        }
+    })
+
+    test(`${m.name} - aggregate the signature shares`, async function () {
+        const sims = await startSims(2)
+        try {
+            const app = apps(sims)
+            const { identities } = await createAccount(app, sims, 2)
+
+            const unsignedTxRaw = await accountTransaction(app(0))
+            const unsignedTx = new UnsignedTransaction(unsignedTxRaw)
+            const txHash = unsignedTx.hash().toString('hex')
+
+            const commitments: string[] = []
+            const nonces: string[] = []
+            for (let i = 0; i < sims.length; i++) {
+                const commitment = await app(i).dkgGetCommitments(PATH, identities, txHash)
+                expect(commitment.returnCode.toString(16)).toEqual("9000")
+                const nonce = await app(i).dkgGetNonces(PATH, identities, txHash)
+                expect(nonce.returnCode.toString(16)).toEqual("9000")
+                if (!commitment.commitments || !nonce.nonces) throw new Error("no commitments found")
+                commitments.push(commitment.commitments.toString('hex'))
+                nonces.push(nonce.nonces.toString('hex'))
+            }
+
+            const signingPackage = new multisig.SigningPackage(
+                Buffer.from(unsignedTx.signingPackageFromRaw(identities, commitments), 'hex')
+            ).frostSigningPackage()
+            const randomizer = Buffer.from(unsignedTx.publicKeyRandomness(), 'hex')
+
+            // The wallet doesn't give the openings of the outputs, so the transaction
+            // can only be signed blindly
+            const shares: Buffer[] = []
+            for (let i = 0; i < sims.length; i++) {
+                await enableBlindSigning(sims[i], m.name)
+                const request = dkgSignTransaction(sims[i], unsignedTx.publicKeyRandomness(),
+                    signingPackage.toString('hex'), nonces[i], unsignedTxRaw)
+                await approveReview(sims[i], "Accept risk")
+                shares.push(await request)
+            }
+
+            const signers = identities.map(i => Buffer.from(i, 'hex'))
+            const aggregated = await sendChunks(sims[0], INS.DKG_AGGREGATE, 0,
+                aggregatePayload(randomizer, signingPackage, signers, shares))
+            expect(aggregated.returnCode.toString(16)).toEqual("9000")
+            expect(aggregated.data[0]).toEqual(0)
+            expect(aggregated.data.length).toEqual(1 + 64)
+
+            // Each share given to the other participant, both are reported
+            const swapped = await sendChunks(sims[0], INS.DKG_AGGREGATE, 0,
+                aggregatePayload(randomizer, signingPackage, signers, [shares[1], shares[0]]))
+            expect(swapped.returnCode.toString(16)).toEqual("9000")
+            expect(swapped.data[0]).toEqual(1)
+            expect(swapped.data[1]).toEqual(2)
+            expect(swapped.data.subarray(2)).toEqual(Buffer.concat(signers))
+        } finally {
+            await closeSims(sims)
+        }
+    })
+
+    test(`${m.name} - sign a batch after a single review`, async function () {
+        const sims = await startSims(2)
+        try {
+            const app = apps(sims)
+            const { identities } = await createAccount(app, sims, 2)
+            const signers = identities.map(i => Buffer.from(i, 'hex'))
+            const entries = 2
+
+            const unsignedTxRaw = await accountTransaction(app(0))
+            const unsignedTx = new UnsignedTransaction(unsignedTxRaw)
+            const txHash = unsignedTx.hash()
+            const randomizer = Buffer.from(unsignedTx.publicKeyRandomness(), 'hex')
+
+            // Every entry has its own nonces, selected by the index after the hash
+            const commitments: string[][] = [...Array(entries)].map(() => [])
+            const nonces: Buffer[][] = []
+            for (let i = 0; i < sims.length; i++) {
+                nonces.push([])
+                for (let entry = 0; entry < entries; entry++) {
+                    const request = Buffer.concat([list(signers), txHash, Buffer.from([entry])])
+
+                    const commitment = await sendChunks(sims[i], INS.DKG_COMMITMENTS, 0, request)
+                    expect(commitment.returnCode.toString(16)).toEqual("9000")
+                    commitments[entry].push(commitment.data.toString('hex'))
+
+                    const nonce = await sendChunks(sims[i], INS.DKG_NONCES, 0, request)
+                    expect(nonce.returnCode.toString(16)).toEqual("9000")
+                    nonces[i].push(nonce.data)
+                }
+            }
+            expect(commitments[0][0]).not.toEqual(commitments[1][0])
+
+            const signingPackages = commitments.map(entryCommitments => new multisig.SigningPackage(
+                Buffer.from(unsignedTx.signingPackageFromRaw(identities, entryCommitments), 'hex')
+            ).frostSigningPackage())
+
+            // Signed blindly, the wallet doesn't give the openings of the outputs
+            const shares: Buffer[][] = []
+            for (let i = 0; i < sims.length; i++) {
+                await enableBlindSigning(sims[i], m.name)
+                const request = sendChunks(sims[i], INS.DKG_SIGN_BATCH, 0, Buffer.concat([
+                    list(signingPackages.map((signingPackage, entry) => Buffer.concat([
+                        u16(randomizer), u16(signingPackage), u16(nonces[i][entry]),
+                    ]))),
+                    u16(unsignedTxRaw),
+                ]))
+                await approveReview(sims[i], "Accept risk")
+
+                const signed = await request
+                expect(signed.returnCode.toString(16)).toEqual("9000")
+                expect(signed.data[0]).toEqual(entries)
+
+                const shareLength = (signed.data.length - 1) / entries
+                shares.push([...Array(entries)].map((_, entry) =>
+                    signed.data.subarray(1 + entry * shareLength, 1 + (entry + 1) * shareLength)))
+            }
+
+            for (let entry = 0; entry < entries; entry++) {
+                const aggregated = await sendChunks(sims[0], INS.DKG_AGGREGATE, 0,
+                    aggregatePayload(randomizer, signingPackages[entry], signers, shares.map(s => s[entry])))
+                expect(aggregated.returnCode.toString(16)).toEqual("9000")
+                expect(aggregated.data[0]).toEqual(0)
+            }
+        } finally {
+            await closeSims(sims)
+        }
+    })
+})
@@ -8,6 +8,7 @@ const APP_PATH_S = resolve('../target/nanos/release/ledger-ironfish')
 const APP_PATH_X = resolve('../target/nanox/release/ledger-ironfish')
 const APP_PATH_SP = resolve('../target/nanosplus/release/ledger-ironfish')
 const APP_PATH_ST = resolve('../target/stax/release/ledger-ironfish')
+const APP_PATH_FL = resolve('../target/flex/release/ledger-ironfish')
 
 export const models: IDeviceModel[] = [
   // TODO investigate why nano s is disabled
@@ -18,6 +19,12 @@ export const models: IDeviceModel[] = [
   //{ name: 'stax', prefix: 'ST', path: APP_PATH_ST },
 ]
 
+// Touch screen devices, whose screens are compared against snapshots
+export const nbglModels: IDeviceModel[] = [
+  { name: 'stax', prefix: 'ST', path: APP_PATH_ST },
+  { name: 'flex', prefix: 'FL', path: APP_PATH_FL },
+]
+
 export const defaultOptions = {
   ...DEFAULT_START_OPTIONS,
   logging: true,
@@ -25,7 +25,7 @@ import {
 import {
     Transaction
 } from '@ironfish/sdk'
-import {buildTx} from "./utils";
+import {approveReview, buildTx, dkgSignTransaction, enableBlindSigning} from "./utils";
 import aggregateRawSignatureShares = multisig.aggregateRawSignatureShares;
 
 jest.setTimeout(4500000)
@@ -76,19 +76,20 @@ describe.each(models)('DKG', function (m) {
                 return {sim, created}
             }
 
-            const runMethod = async (rcvSims: Zemu[], i: number, fn: (app: IronfishApp)=> Promise<any>): Promise<any> => {
+            const runMethod = async (rcvSims: Zemu[], i: number, fn: (app: IronfishApp, sim: Zemu)=> Promise<any>): Promise<any> => {
                 const {sim, created} = checkSimRequired(rcvSims, i)
 
                 try {
                     if(created) await sim.start({...defaultOptions, model: m.name})
                     const app = new IronfishApp(sim.getTransport())
-                    return await fn(app)
+                    return await fn(app, sim)
                 } finally {
                     if(created) await sim.close()
                 }
             }
 
             const globalSims: Zemu[] = [];
+            const blindSigningSims = new Set<Zemu>();
 
             if(ONE_GLOBAL_APP) globalSims.push(new Zemu(m.path))
             else if (ONE_APP_PER_PARTICIPANT) for (let i = 0; i < participants; i++) globalSims.push(new Zemu(m.path))
@@ -124,8 +125,10 @@ describe.each(models)('DKG', function (m) {
                 }
 
                 for(let i = 0; i < participants; i++){
-                    const round1 = await runMethod(globalSims, i, async (app: IronfishApp) => {
-                        const round1 = await app.dkgRound1(PATH, i, identities, minSigners);
+                    const round1 = await runMethod(globalSims, i, async (app: IronfishApp, sim: Zemu) => {
+                        const request = app.dkgRound1(PATH, i, identities, minSigners);
+                        await approveReview(sim, "Approve")
+                        const round1 = await request;
 
                         expect(i + " " + round1.returnCode.toString(16)).toEqual(i + " " + "9000")
                         expect(round1.errorMessage).toEqual('No errors')
@@ -329,13 +332,58 @@ describe.each(models)('DKG', function (m) {
                 const signingPackage = new multisig.SigningPackage(Buffer.from(signingPackageHex, "hex"))
 
                 for(let i = 0; i < participants; i++){
-                    const result = await runMethod(globalSims, i, async (app: IronfishApp) => {
-                        let result = await app.dkgSign(
+                    const result = await runMethod(globalSims, i, async (app: IronfishApp, sim: Zemu) => {
+                        // The first participant sends the transaction, without the openings of
+                        // its outputs, which is no better than the hash alone
+                        if (i == 0) {
+                            // Only the hash could be shown, which is rejected
+                            const rejected = await app.dkgSign(
+                                PATH,
+                                unsignedTx.publicKeyRandomness(),
+                                signingPackage.frostSigningPackage().toString("hex"),
+                                nonces[i].toString("hex")
+                            );
+                            expect(rejected.returnCode.toString(16)).toEqual("b019")
+
+                            await expect(dkgSignTransaction(
+                                sim,
+                                unsignedTx.publicKeyRandomness(),
+                                signingPackage.frostSigningPackage().toString("hex"),
+                                nonces[i].toString("hex"),
+                                unsignedTxRaw
+                            )).rejects.toThrow()
+
+                            if (!blindSigningSims.has(sim)) {
+                                await enableBlindSigning(sim, m.name)
+                                blindSigningSims.add(sim)
+                            }
+
+                            const request = dkgSignTransaction(
+                                sim,
+                                unsignedTx.publicKeyRandomness(),
+                                signingPackage.frostSigningPackage().toString("hex"),
+                                nonces[i].toString("hex"),
+                                unsignedTxRaw
+                            );
+                            await approveReview(sim, "Accept risk")
+
+                            return { signature: await request }
+                        }
+
+                        // Only the signing package is sent, so the app needs blind signing enabled
+                        if (!blindSigningSims.has(sim)) {
+                            await enableBlindSigning(sim, m.name)
+                            blindSigningSims.add(sim)
+                        }
+
+                        const request = app.dkgSign(
                             PATH,
                             unsignedTx.publicKeyRandomness(),
                             signingPackage.frostSigningPackage().toString("hex"),
                             nonces[i].toString("hex")
                         );
+                        await approveReview(sim, "Accept risk")
+                        let result = await request;
 
                         expect(i + " " + result.returnCode.toString(16)).toEqual(i + " " + "9000")
                         expect(result.errorMessage).toEqual('No errors')
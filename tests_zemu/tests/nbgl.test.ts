@@ -0,0 +1,112 @@
+/** ******************************************************************************
+ *  (c) 2018 - 2024 Zondax AG
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ ******************************************************************************* */
+
+import Zemu, {ButtonKind, TouchNavigation} from '@zondax/zemu'
+import {defaultOptions, nbglModels, PATH} from './common'
+import IronfishApp from '@zondax/ledger-ironfish'
+import {enableBlindSigning} from "./utils";
+
+jest.setTimeout(600000)
+
+// Screens of the Stax and Flex user interface. Snapshots are stored in snapshots/,
+// the ones taken by a run are in snapshots-tmp/.
+describe.each(nbglModels)('NBGL', function (m) {
+    const prefix = m.prefix.toLowerCase()
+
+    const getIdentities = async (app: IronfishApp, participants: number) => {
+        const identities: string[] = []
+        for (let i = 0; i < participants; i++) {
+            const identity = await app.dkgGetIdentity(i)
+            expect(identity.returnCode.toString(16)).toEqual("9000")
+            if (!identity.identity) throw new Error("no identity found")
+            identities.push(identity.identity.toString('hex'))
+        }
+        return identities
+    }
+
+    test(`${m.name} - home and settings`, async function () {
+        const sim = new Zemu(m.path)
+        try {
+            await sim.start({ ...defaultOptions, model: m.name })
+
+            const nav = new TouchNavigation(m.name, [
+                ButtonKind.InfoButton,
+                ButtonKind.SettingsNavRightButton,
+                ButtonKind.SettingsQuitButton,
+                ButtonKind.SettingsButton,
+                ButtonKind.SettingsQuitButton,
+            ])
+            await sim.navigateAndCompareSnapshots('.', `${prefix}-home_and_settings`, nav.schedule)
+        } finally {
+            await sim.close()
+        }
+    })
+
+    test(`${m.name} - enable blind signing`, async function () {
+        const sim = new Zemu(m.path)
+        try {
+            await sim.start({ ...defaultOptions, model: m.name })
+
+            await enableBlindSigning(sim, m.name)
+
+            // Settings are returned one byte each, blind signing is the first one
+            const settings = await sim.getTransport().send(0x59, 26, 0, 0)
+            expect(settings[0]).toEqual(1)
+        } finally {
+            await sim.close()
+        }
+    })
+
+    test(`${m.name} - round 1 review, approved`, async function () {
+        const sim = new Zemu(m.path)
+        try {
+            await sim.start({ ...defaultOptions, model: m.name })
+            const app = new IronfishApp(sim.getTransport())
+            const identities = await getIdentities(app, 3)
+
+            const request = app.dkgRound1(PATH, 0, identities, 2)
+            await sim.waitUntilScreenIsNot(sim.getMainMenuSnapshot())
+            // Includes the status screen shown once the review is approved
+            await sim.compareSnapshotsAndApprove('.', `${prefix}-dkg_round_1`)
+
+            const round1 = await request
+            expect(round1.returnCode.toString(16)).toEqual("9000")
+            expect(round1.publicPackage).toBeTruthy()
+            expect(round1.secretPackage).toBeTruthy()
+        } finally {
+            await sim.close()
+        }
+    })
+
+    test(`${m.name} - round 1 review, rejected`, async function () {
+        const sim = new Zemu(m.path)
+        try {
+            await sim.start({ ...defaultOptions, model: m.name })
+            const app = new IronfishApp(sim.getTransport())
+            const identities = await getIdentities(app, 3)
+
+            const request = app.dkgRound1(PATH, 0, identities, 2)
+            await sim.waitUntilScreenIsNot(sim.getMainMenuSnapshot())
+            // Includes the status screen shown once the review is rejected
+            await sim.compareSnapshotsAndReject('.', `${prefix}-dkg_round_1_reject`)
+
+            const round1 = await request
+            expect(round1.returnCode.toString(16)).toEqual("6985")
+        } finally {
+            await sim.close()
+        }
+    })
+})
@@ -1,4 +1,7 @@
+import Zemu, {ButtonKind, IDeviceModel, isTouchDevice, TouchNavigation} from '@zondax/zemu'
 import {Asset, LATEST_TRANSACTION_VERSION, Note, Transaction, makeTestWitness} from '@ironfish/rust-nodejs'
+import IronfishApp, {IronfishKeys} from '@zondax/ledger-ironfish'
+import {PATH} from './common'
 
 export const buildTx = (publicAddress: string, viewKeys: any, proofKey: any) => {
     console.log("here")
@@ -27,4 +30,209 @@ export const buildTx = (publicAddress: string, viewKeys: any, proofKey: any) =>
         intended_fee,
         publicAddress
     );
-}
\ No newline at end of file
+}
+
+// Blind signing is disabled by default. On Nano devices it is the first page of the
+// settings menu, which is the third page of the main menu. On Stax and Flex it is the
+// first switch of the settings.
+export const enableBlindSigning = async (sim: Zemu, model: IDeviceModel['name']) => {
+    if (isTouchDevice(model)) {
+        const nav = new TouchNavigation(model, [
+            ButtonKind.SettingsButton,
+            ButtonKind.ToggleSettingButton1,
+            ButtonKind.SettingsQuitButton,
+        ])
+        await sim.navigate('.', 'enable-blind-signing', nav.schedule, true, false)
+        return
+    }
+
+    await sim.clickRight(undefined, false)
+    await sim.clickRight(undefined, false)
+    await sim.clickBoth(undefined, false)
+    await sim.clickBoth(undefined, false)
+
+    // Back to the main menu, the last page of the settings menu
+    for (let i = 0; i < 4; i++) await sim.clickRight(undefined, false)
+    await sim.clickBoth(undefined, false)
+}
+
+// Go through the review shown while a request is pending, and approve it
+export const approveReview = async (sim: Zemu, approveText: string) => {
+    await sim.waitUntilScreenIsNot(sim.getMainMenuSnapshot())
+    await sim.navigateUntilText('.', 'review', approveText, true, false)
+}
+
+// Go through the review shown while a request is pending, and reject it
+export const rejectReview = async (sim: Zemu) => {
+    await sim.waitUntilScreenIsNot(sim.getMainMenuSnapshot())
+    await sim.navigateUntilText('.', 'review', 'Reject', true, false)
+}
+
+const CLA = 0x59
+const INS_DKG_SIGN = 21
+const CHUNK_SIZE = 250
+
+const withLength = (data: Buffer) => {
+    const len = Buffer.alloc(2)
+    len.writeUInt16BE(data.length)
+    return Buffer.concat([len, data])
+}
+
+// The js client only sends the signing package, so the unsigned transaction the
+// device shows for review is appended here: randomizer | signing package | nonces | tx,
+// each prefixed by its u16 (BE) length.
+export const dkgSignTransaction = async (sim: Zemu, randomness: string, signingPackage: string, nonces: string, tx: Buffer) => {
+    const transport = sim.getTransport()
+    const payload = Buffer.concat([
+        withLength(Buffer.from(randomness, "hex")),
+        withLength(Buffer.from(signingPackage, "hex")),
+        withLength(Buffer.from(nonces, "hex")),
+        withLength(tx),
+    ])
+
+    await transport.send(CLA, INS_DKG_SIGN, 0, 0, Buffer.alloc(0))
+
+    let response = Buffer.alloc(0)
+    for (let pos = 0; pos < payload.length; pos += CHUNK_SIZE) {
+        const last = pos + CHUNK_SIZE >= payload.length
+        response = await transport.send(CLA, INS_DKG_SIGN, last ? 2 : 1, 0, payload.subarray(pos, pos + CHUNK_SIZE), [0x9000])
+    }
+
+    return response.subarray(0, response.length - 2)
+}
+
+const RESPONSE_CHUNK_SIZE = 253
+
+export type RawResponse = { returnCode: number, data: Buffer }
+
+// Sends one APDU and collects the response. Long responses are sent in chunks of
+// 253 bytes, the next one being requested with P1 = 0 and no data.
+const exchange = async (sim: Zemu, ins: number, p1: number, p2: number, data: Buffer): Promise<RawResponse> => {
+    const transport = sim.getTransport()
+
+    let collected = Buffer.alloc(0)
+    let apdu = Buffer.concat([Buffer.from([CLA, ins, p1, p2, data.length]), data])
+    for (;;) {
+        const response = await transport.exchange(apdu)
+        const returnCode = response.readUInt16BE(response.length - 2)
+        const chunk = response.subarray(0, response.length - 2)
+        collected = Buffer.concat([collected, chunk])
+
+        if (returnCode !== 0x9000 || chunk.length < RESPONSE_CHUNK_SIZE) {
+            return { returnCode, data: collected }
+        }
+        apdu = Buffer.from([CLA, ins, 0, p2, 0])
+    }
+}
+
+// Instructions taking their payload in a single APDU
+export const sendApdu = (sim: Zemu, ins: number, p2: number, data: Buffer = Buffer.alloc(0)) =>
+    exchange(sim, ins, 0, p2, data)
+
+// Instructions whose payload is accumulated: P1 = 0 resets it, 1 appends a chunk and
+// 2 appends the last one
+export const sendChunks = async (sim: Zemu, ins: number, p2: number, payload: Buffer): Promise<RawResponse> => {
+    const reset = await exchange(sim, ins, 0, p2, Buffer.alloc(0))
+    if (reset.returnCode !== 0x9000) return reset
+
+    for (let pos = 0; ; pos += CHUNK_SIZE) {
+        const last = pos + CHUNK_SIZE >= payload.length
+        const response = await exchange(sim, ins, last ? 2 : 1, p2, payload.subarray(pos, pos + CHUNK_SIZE))
+        if (last || response.returnCode !== 0x9000) return response
+    }
+}
+
+export const INS = {
+    DKG_COMMITMENTS: 20,
+    DKG_NONCES: 23,
+    DKG_RESHARE_ROUND_1: 28,
+    DKG_RESHARE_ROUND_2: 29,
+    DKG_RESHARE_ROUND_3: 30,
+    DKG_REFRESH_ROUND_1: 31,
+    DKG_REFRESH_ROUND_2: 32,
+    DKG_REFRESH_ROUND_3: 33,
+    DKG_IMPORT_ACCOUNT: 34,
+    DKG_DELETE_ACCOUNT: 35,
+    DKG_AGGREGATE: 36,
+    DKG_SIGN_BATCH: 37,
+    DKG_GET_PARTICIPANTS: 38,
+}
+
+export const u16 = withLength
+
+export const list = (elements: Buffer[]) => Buffer.concat([Buffer.from([elements.length]), ...elements])
+
+// The public package returned by the app starts with the frost public key package,
+// prefixed by its u32 (LE) length
+export const frostPublicKeyPackage = (publicPackage: Buffer) =>
+    publicPackage.subarray(4, 4 + publicPackage.readUInt32LE(0))
+
+// Runs a DKG ceremony with one simulator per participant, the participant i using
+// the identity i. Returns the identities and the address of the account.
+export const createAccount = async (app: (i: number) => IronfishApp, sims: Zemu[], minSigners: number) => {
+    const identities: string[] = []
+    for (let i = 0; i < sims.length; i++) {
+        const identity = await app(i).dkgGetIdentity(i)
+        expect(identity.returnCode.toString(16)).toEqual("9000")
+        if (!identity.identity) throw new Error("no identity found")
+        identities.push(identity.identity.toString('hex'))
+    }
+
+    const round1s: { publicPackage: string, secretPackage: string }[] = []
+    for (let i = 0; i < sims.length; i++) {
+        const request = app(i).dkgRound1(PATH, i, identities, minSigners)
+        await approveReview(sims[i], "Approve")
+        const round1 = await request
+        expect(round1.returnCode.toString(16)).toEqual("9000")
+        if (!round1.publicPackage || !round1.secretPackage) throw new Error("no round 1 found")
+        round1s.push({ publicPackage: round1.publicPackage.toString('hex'), secretPackage: round1.secretPackage.toString('hex') })
+    }
+
+    const round2s: { publicPackage: string, secretPackage: string }[] = []
+    for (let i = 0; i < sims.length; i++) {
+        const round2 = await app(i).dkgRound2(PATH, i, round1s.map(r => r.publicPackage), round1s[i].secretPackage)
+        expect(round2.returnCode.toString(16)).toEqual("9000")
+        if (!round2.publicPackage || !round2.secretPackage) throw new Error("no round 2 found")
+        round2s.push({ publicPackage: round2.publicPackage.toString('hex'), secretPackage: round2.secretPackage.toString('hex') })
+    }
+
+    for (let i = 0; i < sims.length; i++) {
+        const round3 = await app(i).dkgRound3(
+            PATH,
+            i,
+            round1s.map(r => r.publicPackage),
+            round2s.filter((_, pos) => i != pos).map(r => r.publicPackage),
+            round2s[i].secretPackage
+        )
+        expect(round3.returnCode.toString(16)).toEqual("9000")
+    }
+
+    const address = await accountAddress(app(0))
+    return { identities, address }
+}
+
+export const accountAddress = async (app: IronfishApp) => {
+    const keys = await app.dkgRetrieveKeys(IronfishKeys.PublicAddress)
+    expect(keys.returnCode.toString(16)).toEqual("9000")
+    if (!("publicAddress" in keys) || !keys.publicAddress) throw new Error("no address found")
+    return keys.publicAddress.toString('hex')
+}
+
+// Unsigned transaction spending a note of the account, built with its keys
+export const accountTransaction = async (app: IronfishApp) => {
+    const address = await accountAddress(app)
+
+    const viewKeys = await app.dkgRetrieveKeys(IronfishKeys.ViewKey)
+    expect(viewKeys.returnCode.toString(16)).toEqual("9000")
+    if (!("viewKey" in viewKeys) || !viewKeys.viewKey || !viewKeys.ovk) throw new Error("no view keys found")
+
+    const proofKeys = await app.dkgRetrieveKeys(IronfishKeys.ProofGenerationKey)
+    expect(proofKeys.returnCode.toString(16)).toEqual("9000")
+    if (!("nsk" in proofKeys) || !proofKeys.nsk) throw new Error("no proof keys found")
+
+    return buildTx(
+        address,
+        { viewKey: viewKeys.viewKey.toString('hex'), ovk: viewKeys.ovk.toString('hex') },
+        { nsk: proofKeys.nsk.toString('hex') },
+    )
+}